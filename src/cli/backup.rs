@@ -0,0 +1,101 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use log::{debug, info};
+use tokio::fs;
+use tokio::process::Command;
+
+use crate::cli::errors::BackupError;
+use crate::forgejo::models::ForgejoRepository;
+
+/// Exports the repository to the backup directory as `<name>.bundle`, a git bundle of all refs, and
+/// `<name>.json`, the metadata of the repository, empty repositories only get the metadata
+pub async fn backup_repository(
+    repo: &ForgejoRepository,
+    api_key: &str,
+    backup_dir: &Path,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(backup_dir).await?;
+
+    fs::write(
+        backup_dir.join(format!("{}.json", &repo.name)),
+        serde_json::to_string_pretty(repo)?,
+    )
+    .await?;
+
+    if repo.empty {
+        info!(
+            "Backed up metadata of empty repository {} to {}",
+            &repo.full_name,
+            backup_dir.display()
+        );
+        return Ok(());
+    }
+
+    let clone_dir = backup_dir.join(format!("{}.git", &repo.name));
+    let bundle_path = backup_dir.join(format!("{}.bundle", &repo.name));
+
+    // the token is passed as a header through the environment, so it neither ends up in the remote
+    // url of the clone nor in the arguments of the process, which other users can read
+    let authorization = format!("Authorization: token {}", api_key);
+
+    debug!("Cloning repository {} for the backup", &repo.full_name);
+
+    let result = async {
+        run_git(
+            &repo.full_name,
+            Command::new("git")
+                .env("GIT_CONFIG_COUNT", "1")
+                .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+                .env("GIT_CONFIG_VALUE_0", &authorization)
+                .arg("clone")
+                .arg("--mirror")
+                .arg("--quiet")
+                .arg(&repo.clone_url)
+                .arg(&clone_dir),
+        )
+        .await?;
+
+        run_git(
+            &repo.full_name,
+            Command::new("git")
+                .arg("--git-dir")
+                .arg(&clone_dir)
+                .arg("bundle")
+                .arg("create")
+                .arg("--quiet")
+                .arg(&bundle_path)
+                .arg("--all"),
+        )
+        .await
+    }
+    .await;
+
+    if fs::try_exists(&clone_dir).await? {
+        fs::remove_dir_all(&clone_dir).await?;
+    }
+
+    result?;
+
+    info!(
+        "Backed up repository {} to {}",
+        &repo.full_name,
+        bundle_path.display()
+    );
+
+    Ok(())
+}
+
+async fn run_git(repository: &str, command: &mut Command) -> anyhow::Result<()> {
+    let output = command.stdin(Stdio::null()).output().await?;
+
+    if !output.status.success() {
+        return Err(BackupError::GitFailed(
+            repository.to_string(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )
+        .into());
+    }
+
+    Ok(())
+}
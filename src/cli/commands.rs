@@ -1,327 +1,2511 @@
-use lazy_static::lazy_static;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::{Duration, SystemTime};
+
+use futures_util::future;
+use futures_util::stream::{self, LocalBoxStream};
+use futures_util::{StreamExt, TryStreamExt};
 use log::{debug, info, warn};
-use regex::Regex;
-
+use reqwest::Url;
+use tokio::task::JoinSet;
+
+use crate::cli::backup::backup_repository;
+use crate::cli::errors::{DeleteError, MigrationError};
+use crate::cli::progress::MigrationProgress;
+use crate::cli::report::{report_results, MigrationOutcome, MigrationResult};
+use crate::cli::sync::{mirror_description, MetadataChanges};
 use crate::cli::{
-    DeleteForgejoOrganisationCommand, MirrorOrganisationCommand, MirrorRepositoryCommand,
-    MirrorUserCommand,
+    ApplyManifestCommand, ConflictStrategy, DeleteForgejoOrganisationCommand, MirrorGistsCommand,
+    MirrorGiteaOrganisationCommand, MirrorGiteaRepositoryCommand, MirrorGiteaUserCommand,
+    MirrorGitlabGroupCommand, MirrorGitlabRepositoryCommand, MirrorGitlabUserCommand,
+    MirrorMeCommand, MirrorOrganisationCommand, MirrorRepositoryCommand, MirrorStarredCommand,
+    MirrorUserCommand, PruneAction, PruneCommand, RepositoryFilter, RunOptions, SyncCommand,
+    UpdateMirrorSettingsCommand,
 };
 use crate::forgejo::api::ForgejoApi;
+use crate::forgejo::error::ForgejoApiError;
+use crate::forgejo::interval::MirrorInterval;
 use crate::forgejo::models::{
-    ForgejoCreateOrganisationRequest, ForgejoMigrateRepoService, ForgejoMigrateRepositoryRequest,
-    ForgejoVisibility,
+    ForgejoCreateOrganisationRequest, ForgejoEditRepositoryRequest, ForgejoMigrateRepoService,
+    ForgejoMigrateRepositoryRequest, ForgejoMigrationStatus, ForgejoRepository, ForgejoVisibility,
 };
 use crate::github::api::GithubApi;
+use crate::github::constants::ORGANISATION_OWNER_TYPE as GITHUB_ORGANISATION_OWNER_TYPE;
+use crate::github::error::GithubApiError;
 use crate::github::models::GithubRepository;
+use crate::gitlab::api::GitlabApi;
+use crate::gitlab::constants::DEFAULT_URL as GITLAB_DEFAULT_URL;
+use crate::manifest::errors::ManifestError;
+use crate::manifest::load_manifest;
+use crate::manifest::models::{MirrorManifestEntry, MirrorManifestEntryKind, MirrorManifestSource};
+use crate::source::github::disambiguate_gist_names;
+use crate::source::models::{SourceOwner, SourceOwnerKind, SourceRepository};
+use crate::source::SourceProvider;
+use crate::state::models::{JournalRepository, JournalRepositoryStatus};
+use crate::state::Journal;
+use crate::util::time::parse_timestamp;
 
 pub async fn mirror_organisation(cmd: MirrorOrganisationCommand) -> anyhow::Result<()> {
-    let mut github = GithubApi::new(cmd.github_token.clone().unwrap())?;
-    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?;
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    let gh_org = github
-        .get_organisation(&cmd.github_organisation_name)
-        .await?;
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-    let gh_org_username = gh_org.login;
-    let gh_org_display_name = gh_org.name;
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::Organisation,
+            name: cmd.github_organisation_name,
+            forgejo_owner: cmd.org_username,
+            forgejo_display_name: cmd.org_display_name,
+            visibility: Some(cmd.visibility),
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-    let log_name = gh_org_display_name.as_ref().unwrap_or(&gh_org_username);
+    report_results(&results)
+}
 
-    debug!("Fetching repositories of organisation: {}", log_name);
+pub async fn mirror_user(cmd: MirrorUserCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    let repos = github.get_repositories_of_org(&gh_org_username).await?;
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-    let forgejo_org_username = cmd.org_username.unwrap_or(gh_org_username.clone());
-    let forgejo_org_display_name = cmd.org_display_name.or(gh_org_display_name);
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::User,
+            name: cmd.github_user_name,
+            forgejo_owner: cmd.output_organisation_name,
+            forgejo_display_name: None,
+            visibility: Some(cmd.visibility),
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-    let exists = forgejo.organisation_exists(&forgejo_org_username).await?;
+    report_results(&results)
+}
 
-    debug!("Organisation exists: {}", exists);
+pub async fn mirror_repository(cmd: MirrorRepositoryCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    if !exists {
-        debug!("Organisation does not exist, creating it");
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        private: cmd.private,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-        let full_name = if let Some(display_name) = &forgejo_org_display_name {
-            if display_name != &forgejo_org_username {
-                Some(display_name.clone())
-            } else {
-                None
-            }
-        } else {
-            None
+    let results = mirror_single_repository(
+        &mut source,
+        &mut forgejo,
+        &cmd.github_repository_url,
+        MirrorRepositoryOptions {
+            forgejo_owner: cmd.output_owner,
+            forgejo_repository_name: cmd.output_repository_name,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
+
+    report_results(&results)
+}
+
+pub async fn mirror_starred(cmd: MirrorStarredCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        private: cmd.visibility == ForgejoVisibility::Private,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
+
+    debug!(
+        "Fetching starred repositories of user: {}",
+        cmd.github_user_name
+            .as_deref()
+            .unwrap_or("(authenticated user)")
+    );
+
+    // the stars are listed upfront since the organisations have to exist before migrating into them
+    let stars = source
+        .get_starred_repositories(cmd.github_user_name.as_deref())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let repos = filter_github_repositories(stars, &cmd.filter);
+
+    let migrations = if cmd.group_by_owner {
+        group_by_owner(
+            &mut source,
+            &mut forgejo,
+            repos,
+            &HashMap::new(),
+            &cmd.visibility,
+            &base_repository_request,
+            &cmd.run,
+        )
+        .await?
+    } else {
+        // clap requires the organisation unless the repositories are grouped by owner
+        let forgejo_owner = cmd.output_organisation_name.unwrap();
+
+        let user = match cmd.github_user_name {
+            Some(user) => user,
+            None => source.get_authenticated_user().await?.login,
         };
 
-        forgejo
-            .create_organization(&mut ForgejoCreateOrganisationRequest {
-                description: Some(format!(
-                    "Mirror of {}\n\n{}",
-                    gh_org.html_url,
-                    gh_org.description.unwrap_or("".to_string())
-                )),
-                email: None,
-                full_name,
-                location: None,
-                repo_admin_change_team_access: Some(false),
-                username: forgejo_org_username.clone(),
-                visibility: Some(cmd.visibility.clone()),
-                website: gh_org.blog,
-            })
-            .await?;
+        let owner = source.get_owner(SourceOwnerKind::User, &user).await?;
 
-        info!("Created organisation: {}", &forgejo_org_username);
+        let owner = SourceOwner {
+            display_name: None,
+            description: Some(format!("Repositories starred by {}", &owner.login)),
+            html_url: format!("{}?tab=stars", &owner.html_url),
+            ..owner
+        };
 
-        let avatar_bytes = github.get_organisation_avatar(&gh_org_username).await?;
+        create_organisation_if_not_exist(
+            &mut source,
+            &mut forgejo,
+            &owner,
+            &forgejo_owner,
+            None,
+            &cmd.visibility,
+            &cmd.run,
+        )
+        .await?;
 
-        debug!("Setting avatar for organisation: {}", &forgejo_org_username);
+        let request = ForgejoMigrateRepositoryRequest {
+            repo_owner: forgejo_owner,
+            ..base_repository_request
+        };
 
-        forgejo
-            .set_organisation_avatar(&forgejo_org_username, avatar_bytes)
-            .await?;
+        repos
+            .into_iter()
+            .map(|(_, repo)| {
+                let request = migration_request(&request, &repo, &cmd.run);
 
-        info!("Updated avatar for organisation: {}", &forgejo_org_username);
-    }
+                (repo, request)
+            })
+            .collect()
+    };
+
+    let journal = Journal::open(&cmd.run).await?;
+
+    let results = create_migrations_if_not_exist(
+        &mut forgejo,
+        &journal,
+        stream::iter(migrations.into_iter().map(Ok)).boxed_local(),
+        &cmd.run,
+    )
+    .await?;
+
+    report_results(&results)
+}
+
+pub async fn mirror_me(cmd: MirrorMeCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
     let base_repository_request = ForgejoMigrateRepositoryRequest {
-        auth_token: cmd.github_token.clone().unwrap(),
-        clone_addr: "".to_string(),
-        description: None,
         issues: cmd.migrate_issues,
         labels: cmd.migrate_labels,
         lfs: cmd.migrate_lfs,
-        lfs_endpoint: None,
         milestones: cmd.migrate_milestones,
-        mirror: true,
-        mirror_interval: None,
-        private: &cmd.visibility == &ForgejoVisibility::Private,
+        private: cmd.visibility == ForgejoVisibility::Private,
         pull_requests: cmd.migrate_pull_requests,
         releases: cmd.migrate_releases,
-        repo_name: "".to_string(),
-        repo_owner: forgejo_org_username.clone(),
-        service: ForgejoMigrateRepoService::Github,
         wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
     };
 
-    create_migrations_if_not_exist(
+    debug!("Fetching repositories of the authenticated user");
+
+    // the repositories are listed upfront since the organisations have to exist before migrating into them
+    let repos = source
+        .get_repositories_of_authenticated_user(&cmd.affiliations, &cmd.repository_visibility)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let repos = filter_github_repositories(repos, &cmd.filter);
+
+    let migrations = group_by_owner(
+        &mut source,
         &mut forgejo,
-        &forgejo_org_username,
-        &base_repository_request,
         repos,
+        &cmd.owner_map.into_iter().collect(),
+        &cmd.visibility,
+        &base_repository_request,
+        &cmd.run,
     )
     .await?;
 
-    Ok(())
+    let journal = Journal::open(&cmd.run).await?;
+
+    let results = create_migrations_if_not_exist(
+        &mut forgejo,
+        &journal,
+        stream::iter(migrations.into_iter().map(Ok)).boxed_local(),
+        &cmd.run,
+    )
+    .await?;
+
+    report_results(&results)
 }
 
-pub async fn mirror_user(cmd: MirrorUserCommand) -> anyhow::Result<()> {
-    let mut github = GithubApi::new(cmd.github_token.clone().unwrap())?;
-    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?;
+pub async fn mirror_gists(cmd: MirrorGistsCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+
+    // gists are plain git repositories without issues, releases or a wiki, and they are
+    // readable without a token, so the github token isn't handed to forgejo
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        auth_token: "".to_string(),
+        private: cmd.private,
+        repo_owner: cmd.output_owner.clone(),
+        service: ForgejoMigrateRepoService::Git,
+        ..base_repository_request(&source)
+    };
 
-    let gh_user = github.get_user(&cmd.github_user_name).await?;
+    debug!(
+        "Fetching gists of user: {}",
+        cmd.github_user_name
+            .as_deref()
+            .unwrap_or("(authenticated user)")
+    );
+
+    // all gists are listed first, the names of gists with the same description depend on each other
+    let mut repos = source
+        .get_gists(cmd.github_user_name.as_deref())
+        .map_ok(SourceRepository::from)
+        .try_collect::<Vec<_>>()
+        .await?;
 
-    let owner = cmd
-        .output_organisation_name
-        .unwrap_or(gh_user.login.clone());
+    disambiguate_gist_names(&mut repos);
 
-    let exists = forgejo.organisation_exists(&owner).await?;
+    let migrations = repos
+        .into_iter()
+        .map(|repo| {
+            let request = migration_request(&base_repository_request, &repo, &cmd.run);
 
-    debug!("Organisation exists: {}", exists);
+            (repo, request)
+        })
+        .collect::<Vec<_>>();
 
-    if !exists {
-        debug!("Organisation does not exist, creating it");
+    let migrations = stream::iter(migrations.into_iter().map(Ok)).boxed_local();
 
-        let full_name = if let Some(name) = &gh_user.name {
-            if &gh_user.login != name {
-                gh_user.name.clone()
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+    let journal = Journal::open(&cmd.run).await?;
 
-        forgejo
-            .create_organization(&mut ForgejoCreateOrganisationRequest {
-                description: Some(format!("Mirror of {}", gh_user.html_url, )),
-                email: None,
-                full_name,
-                location: None,
-                repo_admin_change_team_access: Some(false),
-                username: owner.clone(),
-                visibility: Some(cmd.visibility.clone()),
-                website: gh_user.blog,
-            })
-            .await?;
+    let results =
+        create_migrations_if_not_exist(&mut forgejo, &journal, migrations, &cmd.run).await?;
 
-        info!("Created organisation: {}", &gh_user.login);
+    report_results(&results)
+}
 
-        let avatar_bytes = github.get_user_avatar(&gh_user.login).await?;
+pub async fn mirror_gitlab_group(cmd: MirrorGitlabGroupCommand) -> anyhow::Result<()> {
+    let mut source = GitlabApi::new(
+        cmd.gitlab_url.unwrap_or(GITLAB_DEFAULT_URL.to_string()),
+        cmd.gitlab_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-        debug!("Setting avatar for organisation: {}", &gh_user.login);
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-        forgejo
-            .set_organisation_avatar(&gh_user.login, avatar_bytes)
-            .await?;
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::Organisation,
+            name: cmd.gitlab_group_path,
+            forgejo_owner: cmd.org_username,
+            forgejo_display_name: cmd.org_display_name,
+            visibility: Some(cmd.visibility),
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-        info!("Updated avatar for organisation: {}", &gh_user.login);
-    }
+    report_results(&results)
+}
 
-    let repos = github.get_repositories_of_user(&gh_user.login).await?;
+pub async fn mirror_gitlab_user(cmd: MirrorGitlabUserCommand) -> anyhow::Result<()> {
+    let mut source = GitlabApi::new(
+        cmd.gitlab_url.unwrap_or(GITLAB_DEFAULT_URL.to_string()),
+        cmd.gitlab_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
     let base_repository_request = ForgejoMigrateRepositoryRequest {
-        auth_token: cmd.github_token.clone().unwrap(),
-        clone_addr: "".to_string(),
-        description: None,
         issues: cmd.migrate_issues,
         labels: cmd.migrate_labels,
         lfs: cmd.migrate_lfs,
-        lfs_endpoint: None,
         milestones: cmd.migrate_milestones,
-        mirror: true,
-        mirror_interval: None,
-        private: &cmd.visibility == &ForgejoVisibility::Private,
         pull_requests: cmd.migrate_pull_requests,
         releases: cmd.migrate_releases,
-        repo_name: "".to_string(),
-        repo_owner: owner.clone(),
-        service: ForgejoMigrateRepoService::Github,
         wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
     };
 
-    create_migrations_if_not_exist(&mut forgejo, &owner, &base_repository_request, repos).await?;
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::User,
+            name: cmd.gitlab_user_name,
+            forgejo_owner: cmd.output_organisation_name,
+            forgejo_display_name: None,
+            visibility: Some(cmd.visibility),
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-    Ok(())
+    report_results(&results)
 }
-pub async fn mirror_repository(cmd: MirrorRepositoryCommand) -> anyhow::Result<()> {
-    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?;
-    let mut github = GithubApi::new(cmd.github_token.clone().unwrap())?;
-
-    lazy_static! {
-        static ref REPO_NAME_REGEX: Regex =
-            Regex::new(r"(?:git@|https://)github.com[:/](?<ownerRepoName>.*)").unwrap();
-    }
-
-    let forgejo_repo_owner = cmd.output_owner;
-
-    if let Some(captures) = REPO_NAME_REGEX.captures(&cmd.github_repository_url) {
-        debug!("Matched repository name: {:?}", captures);
-
-        let named_group = captures.name("ownerRepoName");
-
-        if let Some(matched_group) = named_group {
-            let owner_repo_name = matched_group.as_str();
-            let owner_repo_name_parts = owner_repo_name.split("/").collect::<Vec<&str>>();
-
-            let owner = owner_repo_name_parts[0];
-            let repo_name = owner_repo_name_parts[1];
-
-            debug!("Fetching repository: {}/{}", owner, repo_name);
-
-            let repo = github.get_repository(owner, repo_name).await?;
-
-            let base_repository_request = ForgejoMigrateRepositoryRequest {
-                auth_token: cmd.github_token.clone().unwrap(),
-                clone_addr: repo.html_url.clone(),
-                description: Some(format!(
-                    "[MIRROR] {}",
-                    repo.description.clone().unwrap_or("".to_string())
-                )),
-                issues: cmd.migrate_issues,
-                labels: cmd.migrate_labels,
-                lfs: cmd.migrate_lfs,
-                lfs_endpoint: None,
-                milestones: cmd.migrate_milestones,
-                mirror: true,
-                mirror_interval: None,
-                private: cmd.private,
-                pull_requests: cmd.migrate_pull_requests,
-                releases: cmd.migrate_releases,
-                repo_name: repo_name.to_string(),
-                repo_owner: forgejo_repo_owner.clone(),
-                service: ForgejoMigrateRepoService::Github,
-                wiki: cmd.migrate_wiki,
-            };
 
-            create_migration_if_not_exist(
-                &mut forgejo,
-                &forgejo_repo_owner,
-                &base_repository_request,
-                repo,
-            )
-            .await?;
-        }
-    }
+pub async fn mirror_gitlab_repository(cmd: MirrorGitlabRepositoryCommand) -> anyhow::Result<()> {
+    let mut source = GitlabApi::new(
+        cmd.gitlab_url.unwrap_or(GITLAB_DEFAULT_URL.to_string()),
+        cmd.gitlab_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    Ok(())
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        private: cmd.private,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
+
+    let results = mirror_single_repository(
+        &mut source,
+        &mut forgejo,
+        &cmd.gitlab_repository_url,
+        MirrorRepositoryOptions {
+            forgejo_owner: cmd.output_owner,
+            forgejo_repository_name: cmd.output_repository_name,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
+
+    report_results(&results)
 }
 
-pub async fn delete_forgejo_organisation(
-    cmd: DeleteForgejoOrganisationCommand,
-) -> anyhow::Result<()> {
-    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?;
+pub async fn mirror_gitea_organisation(cmd: MirrorGiteaOrganisationCommand) -> anyhow::Result<()> {
+    let mut source = ForgejoApi::new(cmd.gitea_url.unwrap(), cmd.gitea_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    let repos = forgejo
-        .get_organisation_repositories(&cmd.forgejo_organisation_name)
-        .await?;
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-    for repo in repos {
-        forgejo
-            .delete_repository(&cmd.forgejo_organisation_name, &repo.name)
-            .await?;
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::Organisation,
+            name: cmd.gitea_organisation_name,
+            forgejo_owner: cmd.org_username,
+            forgejo_display_name: cmd.org_display_name,
+            visibility: cmd.visibility,
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-        info!("Deleted repository: {}", &repo.name);
-    }
+    report_results(&results)
+}
 
-    forgejo
-        .delete_organisation(&cmd.forgejo_organisation_name)
-        .await?;
+pub async fn mirror_gitea_user(cmd: MirrorGiteaUserCommand) -> anyhow::Result<()> {
+    let mut source = ForgejoApi::new(cmd.gitea_url.unwrap(), cmd.gitea_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
 
-    info!("Deleted organisation: {}", &cmd.forgejo_organisation_name);
+    let results = mirror_owner(
+        &mut source,
+        &mut forgejo,
+        MirrorOwnerOptions {
+            kind: SourceOwnerKind::User,
+            name: cmd.gitea_user_name,
+            forgejo_owner: cmd.output_organisation_name,
+            forgejo_display_name: None,
+            visibility: cmd.visibility,
+            filter: cmd.filter,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
 
-    Ok(())
+    report_results(&results)
 }
 
-async fn create_migrations_if_not_exist(
-    forgejo: &mut ForgejoApi,
-    forgejo_owner: &str,
-    default_options: &ForgejoMigrateRepositoryRequest,
-    repos: Vec<GithubRepository>,
-) -> anyhow::Result<()> {
-    for repo in repos {
-        let mut options = default_options.clone();
+pub async fn mirror_gitea_repository(cmd: MirrorGiteaRepositoryCommand) -> anyhow::Result<()> {
+    let mut source = ForgejoApi::new(cmd.gitea_url.unwrap(), cmd.gitea_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-        options.repo_name = repo.name.clone();
-        options.clone_addr = repo.clone_url.clone();
-        options.description = Some(format!(
-            "[MIRROR] {}",
-            repo.description.clone().unwrap_or("".to_string())
-        ));
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: cmd.migrate_issues,
+        labels: cmd.migrate_labels,
+        lfs: cmd.migrate_lfs,
+        milestones: cmd.migrate_milestones,
+        private: cmd.private,
+        pull_requests: cmd.migrate_pull_requests,
+        releases: cmd.migrate_releases,
+        wiki: cmd.migrate_wiki,
+        ..base_repository_request(&source)
+    };
+
+    let results = mirror_single_repository(
+        &mut source,
+        &mut forgejo,
+        &cmd.gitea_repository_url,
+        MirrorRepositoryOptions {
+            forgejo_owner: cmd.output_owner,
+            forgejo_repository_name: cmd.output_repository_name,
+        },
+        base_repository_request,
+        &cmd.run,
+    )
+    .await?;
+
+    report_results(&results)
+}
+
+pub async fn apply_manifest(cmd: ApplyManifestCommand) -> anyhow::Result<()> {
+    let manifest = load_manifest(&cmd.manifest).await?;
+
+    let mut forgejo = ForgejoApi::new(
+        cmd.forgejo_url.clone().unwrap(),
+        cmd.forgejo_token.clone().unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+
+    info!(
+        "Applying {} manifest entries from {}",
+        manifest.mirrors.len(),
+        cmd.manifest.display()
+    );
+
+    let mut results = Vec::new();
+
+    for entry in manifest.mirrors {
+        debug!("Applying manifest entry: {:?}", &entry);
+
+        let name = entry.name.clone();
+
+        match apply_manifest_entry(&cmd, &mut forgejo, entry).await {
+            Ok(entry_results) => results.extend(entry_results),
+            Err(err) => results.push(MigrationResult {
+                repository: name,
+                outcome: MigrationOutcome::Failed(err.to_string()),
+            }),
+        }
 
-        create_migration_if_not_exist(forgejo, forgejo_owner, &options, repo).await?;
+        if cmd.run.fail_fast && results.iter().any(MigrationResult::is_failed) {
+            warn!("Stopping after the first failed manifest entry");
+            break;
+        }
     }
 
-    Ok(())
+    report_results(&results)
 }
 
-async fn create_migration_if_not_exist(
-    forgejo: &mut ForgejoApi,
-    forgejo_owner: &str,
-    request: &ForgejoMigrateRepositoryRequest,
-    repo: GithubRepository,
-) -> anyhow::Result<()> {
-    let exists = forgejo.repository_exists(forgejo_owner, &repo.name).await?;
+pub async fn sync_mirrors(cmd: SyncCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+
+    let is_organisation = forgejo.organisation_exists(&cmd.forgejo_owner).await?;
+
+    // repositories are migrated as private into private organisations, so they have to stay private
+    let owner_private = cmd.private
+        || is_organisation
+            && forgejo
+                .get_organisation(&cmd.forgejo_owner)
+                .await?
+                .visibility
+                == ForgejoVisibility::Private;
+
+    debug!(
+        "Fetching repositories of forgejo owner: {}",
+        &cmd.forgejo_owner
+    );
+
+    let repos =
+        get_forgejo_owner_repositories(&mut forgejo, &cmd.forgejo_owner, is_organisation).await?;
+
+    let mut results = Vec::new();
 
-    if exists {
-        warn!("Repository already exists: {}, skipping", &repo.name);
-        return Ok(());
+    for repo in repos {
+        if !is_mirror_of(&repo, source.host()) {
+            debug!(
+                "Repository is not a mirror of a github repository: {}, skipping",
+                &repo.full_name
+            );
+            continue;
+        }
+
+        let outcome = sync_mirror(
+            &mut source,
+            &mut forgejo,
+            &repo,
+            owner_private,
+            cmd.sync_visibility,
+            &cmd.run,
+        )
+        .await;
+        let result = MigrationResult::new(&cmd.forgejo_owner, &repo.name, outcome);
+        let failed = result.is_failed();
+
+        results.push(result);
+
+        if failed && cmd.run.fail_fast {
+            warn!(
+                "Stopped after the first failed sync, the remaining repositories were not synced"
+            );
+            break;
+        }
     }
 
-    let repo_name = repo.name;
+    report_results(&results)
+}
+
+pub async fn update_mirror_settings(cmd: UpdateMirrorSettingsCommand) -> anyhow::Result<()> {
+    let mirror_interval = cmd.run.mirror_interval.unwrap();
 
-    debug!("Migrating repository: {}", &repo_name);
+    let mut source = match cmd.github_token {
+        Some(github_token) => Some(
+            GithubApi::new(cmd.github_api_url, cmd.github_host, github_token)?
+                .with_retry_policy(cmd.run.retry_policy()),
+        ),
+        None => None,
+    };
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
 
-    forgejo.mirror_repository(&request).await?;
+    let is_organisation = forgejo.organisation_exists(&cmd.forgejo_owner).await?;
 
-    info!("Repository mirrored: {}", &repo_name);
+    debug!(
+        "Fetching repositories of forgejo owner: {}",
+        &cmd.forgejo_owner
+    );
 
-    Ok(())
+    let repos =
+        get_forgejo_owner_repositories(&mut forgejo, &cmd.forgejo_owner, is_organisation).await?;
+
+    let mut results = Vec::new();
+
+    for repo in repos {
+        // the automatic interval needs the last push, which is only known for mirrors of github repositories
+        let source = match (&mut source, mirror_interval) {
+            (Some(source), MirrorInterval::Auto) if is_mirror_of(&repo, source.host()) => {
+                Some(source)
+            }
+            (_, MirrorInterval::Fixed(_)) if repo.mirror => None,
+            _ => {
+                debug!(
+                    "Repository is not a mirror with a known interval: {}, skipping",
+                    &repo.full_name
+                );
+                continue;
+            }
+        };
+
+        let outcome =
+            update_mirror_interval(source, &mut forgejo, &repo, &mirror_interval, &cmd.run).await;
+        let result = MigrationResult::new(&cmd.forgejo_owner, &repo.name, outcome);
+        let failed = result.is_failed();
+
+        results.push(result);
+
+        if failed && cmd.run.fail_fast {
+            warn!(
+                "Stopped after the first failed update, the remaining repositories were not updated"
+            );
+            break;
+        }
+    }
+
+    report_results(&results)
+}
+
+pub async fn prune_mirrors(cmd: PruneCommand) -> anyhow::Result<()> {
+    let mut source = GithubApi::new(
+        cmd.github_api_url,
+        cmd.github_host,
+        cmd.github_token.unwrap(),
+    )?
+    .with_retry_policy(cmd.run.retry_policy());
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?
+        .with_retry_policy(cmd.run.retry_policy());
+
+    let github_owner = cmd.github_owner.unwrap_or(cmd.forgejo_owner.clone());
+
+    // organisations are served by the users endpoint as well, it tells both apart
+    let kind = if source.get_user(&github_owner).await?.type_field == GITHUB_ORGANISATION_OWNER_TYPE
+    {
+        SourceOwnerKind::Organisation
+    } else {
+        SourceOwnerKind::User
+    };
+
+    let owner = source.get_owner(kind, &github_owner).await?;
+
+    debug!("Fetching repositories of {}: {}", owner.kind, &owner.login);
+
+    // the complete listing is required, a partial listing would make every missing repository an orphan
+    let listed = source
+        .get_repositories(&owner)
+        .map_ok(|repo| normalize_repository_url(&repo.clone_url))
+        .try_collect::<HashSet<_>>()
+        .await?;
+
+    let is_organisation = forgejo.organisation_exists(&cmd.forgejo_owner).await?;
+
+    debug!(
+        "Fetching repositories of forgejo owner: {}",
+        &cmd.forgejo_owner
+    );
+
+    let repos =
+        get_forgejo_owner_repositories(&mut forgejo, &cmd.forgejo_owner, is_organisation).await?;
+
+    let mut results = Vec::new();
+    let mut orphans = Vec::new();
+
+    for repo in repos {
+        if !is_mirror_of(&repo, source.host())
+            || listed.contains(&normalize_repository_url(&repo.original_url))
+        {
+            continue;
+        }
+
+        match orphan_reason(&mut source, &repo).await {
+            Ok(Some(reason)) => orphans.push((repo, reason)),
+            Ok(None) => debug!(
+                "Repository {} isn't listed but its source still exists, keeping it",
+                &repo.full_name
+            ),
+            Err(err) => results.push(MigrationResult::new(
+                &cmd.forgejo_owner,
+                &repo.name,
+                Err(err),
+            )),
+        }
+    }
+
+    if orphans.is_empty() {
+        info!("No orphaned mirrors found in {}", &cmd.forgejo_owner);
+
+        // the mirrors whose source couldn't be looked up are still reported
+        return if results.is_empty() {
+            Ok(())
+        } else {
+            report_results(&results)
+        };
+    }
+
+    if cmd.action != PruneAction::Report && !cmd.run.dry_run && !cmd.yes {
+        let action = match cmd.action {
+            PruneAction::Archive => "archives",
+            _ => "deletes",
+        };
+
+        confirm(
+            &format!(
+                "This {} {} orphaned mirrors of {}",
+                action,
+                orphans.len(),
+                &cmd.forgejo_owner
+            ),
+            &cmd.forgejo_owner,
+        )?;
+    }
+
+    for (mirror, reason) in orphans {
+        let outcome = prune_mirror(
+            &mut forgejo,
+            &mirror,
+            reason,
+            &cmd.action,
+            cmd.keep_archived,
+            &cmd.run,
+        )
+        .await;
+        let result = MigrationResult::new(&cmd.forgejo_owner, &mirror.name, outcome);
+        let failed = result.is_failed();
+
+        results.push(result);
+
+        if failed && cmd.run.fail_fast {
+            warn!(
+                "Stopped after the first failed prune, the remaining repositories were not pruned"
+            );
+            break;
+        }
+    }
+
+    report_results(&results)
+}
+
+pub async fn delete_forgejo_organisation(
+    cmd: DeleteForgejoOrganisationCommand,
+) -> anyhow::Result<()> {
+    let mut forgejo = ForgejoApi::new(cmd.forgejo_url.unwrap(), cmd.forgejo_token.unwrap())?;
+
+    let organisation = &cmd.forgejo_organisation_name;
+
+    // all repositories are listed before deleting any, deleting them would shift the pages
+    let repos = forgejo
+        .get_organisation_repositories(organisation)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let non_mirrors = repos
+        .iter()
+        .filter(|repo| !repo.mirror)
+        .map(|repo| repo.name.as_str())
+        .collect::<Vec<_>>();
+
+    if !non_mirrors.is_empty() {
+        if !cmd.include_non_mirrors {
+            return Err(DeleteError::NonMirrorRepositories(
+                organisation.clone(),
+                non_mirrors.join(", "),
+            )
+            .into());
+        }
+
+        warn!(
+            "Deleting repositories which aren't mirrors: {}",
+            non_mirrors.join(", ")
+        );
+    }
+
+    if !cmd.yes {
+        confirm(
+            &format!(
+                "This deletes the organisation {} and its {} repositories",
+                organisation,
+                repos.len()
+            ),
+            organisation,
+        )?;
+    }
+
+    for repo in repos {
+        if let Some(backup_dir) = &cmd.backup_dir {
+            backup_repository(&repo, forgejo.api_key(), backup_dir).await?;
+        }
+
+        forgejo.delete_repository(organisation, &repo.name).await?;
+
+        info!("Deleted repository: {}", &repo.name);
+    }
+
+    forgejo.delete_organisation(organisation).await?;
+
+    info!("Deleted organisation: {}", organisation);
+
+    Ok(())
+}
+
+/// Asks the user to type the expected name, nothing is changed if it doesn't match
+fn confirm(message: &str, expected: &str) -> anyhow::Result<()> {
+    eprintln!("{}, type {} to confirm:", message, expected);
+
+    let mut confirmation = String::new();
+    io::stdin().read_line(&mut confirmation)?;
+
+    if confirmation.trim() != expected {
+        return Err(DeleteError::ConfirmationMismatch(expected.to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// Updates the metadata of the mirror to match its source repository, private mirrors are only made
+/// public if `sync_visibility` is set
+async fn sync_mirror<P: SourceProvider>(
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    mirror: &ForgejoRepository,
+    owner_private: bool,
+    sync_visibility: bool,
+    run: &RunOptions,
+) -> anyhow::Result<MigrationOutcome> {
+    let repo = source.get_repository(&mirror.original_url).await?;
+
+    // mirrors may have been created private on purpose, so they aren't exposed unless asked to
+    let private = owner_private || repo.private || (mirror.private && !sync_visibility);
+
+    let changes = MetadataChanges::between(mirror, &repo, private, run.mirror_topics(&repo));
+
+    if changes.is_empty() {
+        debug!("Repository is up to date: {}", &mirror.full_name);
+        return Ok(MigrationOutcome::Unchanged);
+    }
+
+    let fields = changes.fields.join(", ");
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would update repository: {} ({})",
+            &mirror.full_name, &fields
+        );
+        return Ok(MigrationOutcome::Planned);
+    }
+
+    // the topics of archived repositories can't be changed, so they are set before archiving and after unarchiving
+    let archiving = changes.edit.archived == Some(true);
+
+    if archiving {
+        if let Some(topics) = changes.topics.clone() {
+            forgejo
+                .set_repository_topics(&mirror.owner.login, &mirror.name, topics)
+                .await?;
+        }
+    }
+
+    if changes.has_edits() {
+        forgejo
+            .edit_repository(&mirror.owner.login, &mirror.name, &changes.edit)
+            .await?;
+    }
+
+    if !archiving {
+        if let Some(topics) = changes.topics {
+            forgejo
+                .set_repository_topics(&mirror.owner.login, &mirror.name, topics)
+                .await?;
+        }
+    }
+
+    info!("Repository updated: {} ({})", &mirror.full_name, &fields);
+
+    Ok(MigrationOutcome::Updated(fields))
+}
+
+/// Changes the mirror interval of the mirror, the last push to the source repository is only
+/// fetched for the automatic interval
+async fn update_mirror_interval(
+    source: Option<&mut GithubApi>,
+    forgejo: &mut ForgejoApi,
+    mirror: &ForgejoRepository,
+    mirror_interval: &MirrorInterval,
+    run: &RunOptions,
+) -> anyhow::Result<MigrationOutcome> {
+    let pushed_at = match source {
+        Some(source) => {
+            SourceProvider::get_repository(source, &mirror.original_url)
+                .await?
+                .pushed_at
+        }
+        None => None,
+    };
+
+    let interval = mirror_interval.resolve(pushed_at.as_deref());
+
+    if mirror.mirror_interval == interval {
+        debug!(
+            "Mirror interval is up to date: {} ({})",
+            &mirror.full_name, &interval
+        );
+        return Ok(MigrationOutcome::Unchanged);
+    }
+
+    let change = format!(
+        "mirror interval {} -> {}",
+        &mirror.mirror_interval, &interval
+    );
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would update repository: {} ({})",
+            &mirror.full_name, &change
+        );
+        return Ok(MigrationOutcome::Planned);
+    }
+
+    forgejo
+        .edit_repository(
+            &mirror.owner.login,
+            &mirror.name,
+            &ForgejoEditRepositoryRequest {
+                mirror_interval: Some(interval),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    info!("Repository updated: {} ({})", &mirror.full_name, &change);
+
+    Ok(MigrationOutcome::Updated(change))
+}
+
+async fn get_forgejo_owner_repositories(
+    forgejo: &mut ForgejoApi,
+    forgejo_owner: &str,
+    is_organisation: bool,
+) -> anyhow::Result<Vec<ForgejoRepository>> {
+    if is_organisation {
+        forgejo.get_organisation_repositories(forgejo_owner)
+    } else {
+        forgejo.get_user_repositories(forgejo_owner)
+    }
+    .try_collect::<Vec<_>>()
+    .await
+}
+
+/// Looks up the source of a mirror which is missing from the listing, repositories which the
+/// listing doesn't include, e.g. private repositories of users, still exist and aren't orphans
+async fn orphan_reason(
+    source: &mut GithubApi,
+    mirror: &ForgejoRepository,
+) -> anyhow::Result<Option<String>> {
+    match SourceProvider::get_repository(source, &mirror.original_url).await {
+        Ok(repo)
+            if normalize_repository_url(&repo.clone_url)
+                == normalize_repository_url(&mirror.original_url) =>
+        {
+            Ok(None)
+        }
+        Ok(repo) => Ok(Some(format!("moved to {}", &repo.full_name))),
+        Err(err)
+            if err
+                .downcast_ref::<GithubApiError>()
+                .is_some_and(GithubApiError::is_not_found) =>
+        {
+            Ok(Some("deleted".to_string()))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Archives or deletes the orphaned mirror, or only reports it
+async fn prune_mirror(
+    forgejo: &mut ForgejoApi,
+    mirror: &ForgejoRepository,
+    reason: String,
+    action: &PruneAction,
+    keep_archived: bool,
+    run: &RunOptions,
+) -> anyhow::Result<MigrationOutcome> {
+    match action {
+        PruneAction::Report => return Ok(MigrationOutcome::Orphaned(reason)),
+        PruneAction::Archive if mirror.archived => {
+            return Ok(MigrationOutcome::Orphaned(format!(
+                "{}, already archived",
+                reason
+            )))
+        }
+        PruneAction::Delete if mirror.archived && keep_archived => {
+            return Ok(MigrationOutcome::Orphaned(format!(
+                "{}, kept archived",
+                reason
+            )))
+        }
+        _ => {}
+    }
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would {} orphaned repository: {} ({})",
+            if *action == PruneAction::Archive {
+                "archive"
+            } else {
+                "delete"
+            },
+            &mirror.full_name,
+            &reason
+        );
+        return Ok(MigrationOutcome::Planned);
+    }
+
+    if *action == PruneAction::Archive {
+        forgejo
+            .edit_repository(
+                &mirror.owner.login,
+                &mirror.name,
+                &ForgejoEditRepositoryRequest {
+                    archived: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        info!(
+            "Archived orphaned repository: {} ({})",
+            &mirror.full_name, &reason
+        );
+
+        return Ok(MigrationOutcome::Archived(reason));
+    }
+
+    forgejo
+        .delete_repository(&mirror.owner.login, &mirror.name)
+        .await?;
+
+    info!(
+        "Deleted orphaned repository: {} ({})",
+        &mirror.full_name, &reason
+    );
+
+    Ok(MigrationOutcome::Deleted(reason))
+}
+
+/// Clone urls and the original urls of mirrors only differ in case and the `.git` suffix
+fn normalize_repository_url(url: &str) -> String {
+    let url = url.trim_end_matches('/').to_lowercase();
+
+    url.strip_suffix(".git").unwrap_or(&url).to_string()
+}
+
+/// Whether the repository is a mirror of a repository on the host, which includes the port unless
+/// it's the default port of the scheme, e.g. `localhost:8080`
+fn is_mirror_of(repo: &ForgejoRepository, host: &str) -> bool {
+    repo.mirror
+        && Url::parse(&repo.original_url).is_ok_and(|url| {
+            let authority = match (url.host_str(), url.port()) {
+                (Some(url_host), Some(port)) => format!("{}:{}", url_host, port),
+                (Some(url_host), None) => url_host.to_string(),
+                (None, _) => return false,
+            };
+
+            authority.eq_ignore_ascii_case(host)
+        })
+}
+
+struct MirrorOwnerOptions {
+    kind: SourceOwnerKind,
+    name: String,
+    forgejo_owner: Option<String>,
+    forgejo_display_name: Option<String>,
+    visibility: Option<ForgejoVisibility>,
+    filter: RepositoryFilter,
+}
+
+struct MirrorRepositoryOptions {
+    forgejo_owner: String,
+    forgejo_repository_name: Option<String>,
+}
+
+fn base_repository_request<P: SourceProvider>(source: &P) -> ForgejoMigrateRepositoryRequest {
+    ForgejoMigrateRepositoryRequest {
+        auth_token: source.auth_token(),
+        clone_addr: "".to_string(),
+        description: None,
+        issues: false,
+        labels: false,
+        lfs: false,
+        lfs_endpoint: None,
+        milestones: false,
+        mirror: true,
+        mirror_interval: None,
+        private: false,
+        pull_requests: false,
+        releases: false,
+        repo_name: "".to_string(),
+        repo_owner: "".to_string(),
+        service: source.service(),
+        wiki: false,
+    }
+}
+
+async fn mirror_owner<P: SourceProvider>(
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    options: MirrorOwnerOptions,
+    base_repository_request: ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Vec<MigrationResult>> {
+    let journal = Journal::open(run).await?;
+
+    let owner = source.get_owner(options.kind, &options.name).await?;
+
+    let forgejo_owner = options.forgejo_owner.unwrap_or(owner.name.clone());
+    let visibility = options
+        .visibility
+        .or(owner.visibility.clone())
+        .unwrap_or_default();
+
+    let resumed_repos = if run.resume {
+        journal
+            .owner_repositories(&owner.html_url, &forgejo_owner)
+            .await
+    } else {
+        None
+    };
+
+    let listed = Cell::new(0);
+    let filtered = Cell::new(0);
+
+    let repos = match resumed_repos {
+        Some(repos) => {
+            info!(
+                "Resuming {} repositories of {} {} from the state file",
+                repos.len(),
+                owner.kind,
+                &owner.login
+            );
+
+            stream::iter(repos.into_iter().map(Ok)).boxed_local()
+        }
+        None => {
+            create_organisation_if_not_exist(
+                source,
+                forgejo,
+                &owner,
+                &forgejo_owner,
+                options.forgejo_display_name,
+                &visibility,
+                run,
+            )
+            .await?;
+
+            journal.start_owner(&owner.html_url, &forgejo_owner).await?;
+
+            debug!("Fetching repositories of {}: {}", owner.kind, &owner.login);
+
+            // the repositories are recorded as they are listed, filtered out repositories included,
+            // so a resumed run filters the same listing again
+            source
+                .get_repositories(&owner)
+                .and_then(|repo| async {
+                    journal
+                        .record_owner_repository(&owner.html_url, &forgejo_owner, &repo)
+                        .await?;
+
+                    Ok(repo)
+                })
+                .boxed_local()
+        }
+    };
+
+    let request = ForgejoMigrateRepositoryRequest {
+        private: visibility == ForgejoVisibility::Private,
+        repo_owner: forgejo_owner.clone(),
+        ..base_repository_request
+    };
+
+    let migrations = repos
+        .try_filter(|repo| {
+            listed.set(listed.get() + 1);
+
+            let matches = options.filter.matches(repo);
+
+            if !matches {
+                filtered.set(filtered.get() + 1);
+            }
+
+            future::ready(matches)
+        })
+        .map_ok(|repo| {
+            let request = migration_request(&request, &repo, run);
+
+            (repo, request)
+        })
+        .boxed_local();
+
+    let results = create_migrations_if_not_exist(forgejo, &journal, migrations, run).await?;
+
+    if filtered.get() > 0 {
+        info!(
+            "Filtered out {} of {} repositories",
+            filtered.get(),
+            listed.get()
+        );
+    }
+
+    // a fail fast run stops listing after the first failure, so only runs without failures listed everything
+    let listing_complete = !run.fail_fast || !results.iter().any(MigrationResult::is_failed);
+
+    if listing_complete {
+        journal
+            .complete_owner(&owner.html_url, &forgejo_owner)
+            .await?;
+    }
+
+    Ok(results)
+}
+
+async fn mirror_single_repository<P: SourceProvider>(
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    url: &str,
+    options: MirrorRepositoryOptions,
+    base_repository_request: ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Vec<MigrationResult>> {
+    debug!("Fetching repository: {}", url);
+
+    let repo = source.get_repository(url).await?;
+
+    let mut request = migration_request(&base_repository_request, &repo, run);
+
+    request.repo_owner = options.forgejo_owner.clone();
+
+    if let Some(repo_name) = options.forgejo_repository_name {
+        request.repo_name = repo_name;
+    }
+
+    let journal = Journal::open(run).await?;
+
+    let outcome = create_journaled_migration(forgejo, &journal, &repo, &request, run).await;
+
+    Ok(vec![MigrationResult::new(
+        &options.forgejo_owner,
+        &request.repo_name,
+        outcome,
+    )])
+}
+
+async fn apply_manifest_entry(
+    cmd: &ApplyManifestCommand,
+    forgejo: &mut ForgejoApi,
+    entry: MirrorManifestEntry,
+) -> anyhow::Result<Vec<MigrationResult>> {
+    let missing_argument = |argument: &str| {
+        ManifestError::MissingSourceArgument(entry.name.clone(), argument.to_string())
+    };
+
+    match entry.source {
+        MirrorManifestSource::Github => {
+            let token = entry
+                .source_token
+                .clone()
+                .or(cmd.github_token.clone())
+                .ok_or(missing_argument("github-token"))?;
+
+            // the host belongs to the api url of the cli, so it is derived from the api url of the entry instead
+            let (api_url, host) = match entry.source_url.clone() {
+                Some(api_url) => (Some(api_url), None),
+                None => (cmd.github_api_url.clone(), cmd.github_host.clone()),
+            };
+
+            let mut source =
+                GithubApi::new(api_url, host, token)?.with_retry_policy(cmd.run.retry_policy());
+
+            apply_manifest_entry_with_source(cmd, &mut source, forgejo, entry).await
+        }
+        MirrorManifestSource::Gitlab => {
+            let url = entry
+                .source_url
+                .clone()
+                .or(cmd.gitlab_url.clone())
+                .unwrap_or(GITLAB_DEFAULT_URL.to_string());
+            let token = entry
+                .source_token
+                .clone()
+                .or(cmd.gitlab_token.clone())
+                .ok_or(missing_argument("gitlab-token"))?;
+
+            let mut source = GitlabApi::new(url, token)?.with_retry_policy(cmd.run.retry_policy());
+
+            apply_manifest_entry_with_source(cmd, &mut source, forgejo, entry).await
+        }
+        MirrorManifestSource::Gitea => {
+            let url = entry
+                .source_url
+                .clone()
+                .or(cmd.gitea_url.clone())
+                .ok_or(missing_argument("gitea-url"))?;
+            let token = entry
+                .source_token
+                .clone()
+                .or(cmd.gitea_token.clone())
+                .ok_or(missing_argument("gitea-token"))?;
+
+            let mut source = ForgejoApi::new(url, token)?.with_retry_policy(cmd.run.retry_policy());
+
+            apply_manifest_entry_with_source(cmd, &mut source, forgejo, entry).await
+        }
+    }
+}
+
+async fn apply_manifest_entry_with_source<P: SourceProvider>(
+    cmd: &ApplyManifestCommand,
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    entry: MirrorManifestEntry,
+) -> anyhow::Result<Vec<MigrationResult>> {
+    let run = RunOptions {
+        mirror_interval: entry.mirror_interval.or(cmd.run.mirror_interval),
+        ..cmd.run.clone()
+    };
+
+    // repositories mirrored into a private organisation are private, like with the mirror-org command
+    let private = entry
+        .private
+        .unwrap_or(cmd.private || entry.visibility == Some(ForgejoVisibility::Private));
+
+    let base_repository_request = ForgejoMigrateRepositoryRequest {
+        issues: entry.migrate_issues.unwrap_or(cmd.migrate_issues),
+        labels: entry.migrate_labels.unwrap_or(cmd.migrate_labels),
+        lfs: entry.migrate_lfs.unwrap_or(cmd.migrate_lfs),
+        milestones: entry.migrate_milestones.unwrap_or(cmd.migrate_milestones),
+        private,
+        pull_requests: entry
+            .migrate_pull_requests
+            .unwrap_or(cmd.migrate_pull_requests),
+        releases: entry.migrate_releases.unwrap_or(cmd.migrate_releases),
+        wiki: entry.migrate_wiki.unwrap_or(cmd.migrate_wiki),
+        ..base_repository_request(source)
+    };
+
+    let kind = match entry.kind {
+        MirrorManifestEntryKind::Organisation => SourceOwnerKind::Organisation,
+        MirrorManifestEntryKind::User => SourceOwnerKind::User,
+        MirrorManifestEntryKind::Repository => {
+            let forgejo_owner = entry
+                .target_owner
+                .ok_or(ManifestError::MissingTargetOwner(entry.name.clone()))?;
+
+            return mirror_single_repository(
+                source,
+                forgejo,
+                &entry.name,
+                MirrorRepositoryOptions {
+                    forgejo_owner,
+                    forgejo_repository_name: entry.target_repository_name,
+                },
+                base_repository_request,
+                &run,
+            )
+            .await;
+        }
+    };
+
+    mirror_owner(
+        source,
+        forgejo,
+        MirrorOwnerOptions {
+            kind,
+            name: entry.name,
+            forgejo_owner: entry.target_owner,
+            forgejo_display_name: entry.target_display_name,
+            visibility: entry.visibility,
+            filter: cmd.filter.clone(),
+        },
+        base_repository_request,
+        &run,
+    )
+    .await
+}
+
+/// Removes the repositories which don't match the filter and determines whether their owners are
+/// organisations or users
+fn filter_github_repositories(
+    repos: Vec<GithubRepository>,
+    filter: &RepositoryFilter,
+) -> Vec<(SourceOwnerKind, SourceRepository)> {
+    let total = repos.len();
+
+    let repos = repos
+        .into_iter()
+        .map(|repo| {
+            let kind = if repo.owner.type_field == GITHUB_ORGANISATION_OWNER_TYPE {
+                SourceOwnerKind::Organisation
+            } else {
+                SourceOwnerKind::User
+            };
+
+            (kind, SourceRepository::from(repo))
+        })
+        .filter(|(_, repo)| filter.matches(repo))
+        .collect::<Vec<(SourceOwnerKind, SourceRepository)>>();
+
+    if repos.len() < total {
+        info!(
+            "Filtered out {} of {} repositories",
+            total - repos.len(),
+            total
+        );
+    }
+
+    repos
+}
+
+/// Creates a forgejo organisation for every owner of the repositories and returns the migration of
+/// every repository into the organisation of its owner, which is named after the owner unless the
+/// owner map contains another name
+async fn group_by_owner<P: SourceProvider>(
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    repos: Vec<(SourceOwnerKind, SourceRepository)>,
+    owner_map: &HashMap<String, String>,
+    visibility: &ForgejoVisibility,
+    base_repository_request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Vec<(SourceRepository, ForgejoMigrateRepositoryRequest)>> {
+    let mut forgejo_owners = HashMap::new();
+    let mut migrations = Vec::with_capacity(repos.len());
+
+    for (kind, repo) in repos {
+        let forgejo_owner = match forgejo_owners.get(&repo.owner) {
+            Some(forgejo_owner) => forgejo_owner,
+            None => {
+                let owner = source.get_owner(kind, &repo.owner).await?;
+
+                let forgejo_owner = owner_map
+                    .get(&repo.owner)
+                    .cloned()
+                    .unwrap_or(owner.name.clone());
+
+                create_organisation_if_not_exist(
+                    source,
+                    forgejo,
+                    &owner,
+                    &forgejo_owner,
+                    None,
+                    visibility,
+                    run,
+                )
+                .await?;
+
+                forgejo_owners
+                    .entry(repo.owner.clone())
+                    .or_insert(forgejo_owner)
+            }
+        };
+
+        let request = migration_request(
+            &ForgejoMigrateRepositoryRequest {
+                repo_owner: forgejo_owner.clone(),
+                ..base_repository_request.clone()
+            },
+            &repo,
+            run,
+        );
+
+        migrations.push((repo, request));
+    }
+
+    Ok(migrations)
+}
+
+async fn create_organisation_if_not_exist<P: SourceProvider>(
+    source: &mut P,
+    forgejo: &mut ForgejoApi,
+    owner: &SourceOwner,
+    forgejo_owner: &str,
+    display_name: Option<String>,
+    visibility: &ForgejoVisibility,
+    run: &RunOptions,
+) -> anyhow::Result<()> {
+    let exists = forgejo.organisation_exists(forgejo_owner).await?;
+
+    debug!("Organisation exists: {}", exists);
+
+    if exists {
+        return Ok(());
+    }
+
+    debug!("Organisation does not exist, creating it");
+
+    let full_name = display_name
+        .or(owner.display_name.clone())
+        .filter(|name| !name.is_empty() && name != forgejo_owner);
+
+    let description = match &owner.description {
+        Some(description) if !description.is_empty() => {
+            format!("Mirror of {}\n\n{}", owner.html_url, description)
+        }
+        _ => format!("Mirror of {}", owner.html_url),
+    };
+
+    let mut request = ForgejoCreateOrganisationRequest {
+        description: Some(description),
+        email: None,
+        full_name,
+        location: owner.location.clone(),
+        repo_admin_change_team_access: Some(false),
+        username: forgejo_owner.to_string(),
+        visibility: Some(visibility.clone()),
+        website: owner.website.clone(),
+    };
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would create organisation: {} (full name: {}, visibility: {:?}, website: {})",
+            forgejo_owner,
+            request.full_name.as_deref().unwrap_or("-"),
+            visibility,
+            request.website.as_deref().unwrap_or("-")
+        );
+    } else {
+        forgejo.create_organization(&mut request).await?;
+
+        info!("Created organisation: {}", forgejo_owner);
+    }
+
+    if let Some(avatar_bytes) = source.get_owner_avatar(owner).await? {
+        if run.dry_run {
+            info!(
+                "[DRY RUN] Would set avatar ({} bytes) for organisation: {}",
+                avatar_bytes.len(),
+                forgejo_owner
+            );
+
+            return Ok(());
+        }
+
+        debug!("Setting avatar for organisation: {}", forgejo_owner);
+
+        forgejo
+            .set_organisation_avatar(forgejo_owner, avatar_bytes)
+            .await?;
+
+        info!("Updated avatar for organisation: {}", forgejo_owner);
+    }
+
+    Ok(())
+}
+
+/// Migrates the repositories while they are listed, up to the configured concurrency at once
+async fn create_migrations_if_not_exist(
+    forgejo: &mut ForgejoApi,
+    journal: &Journal,
+    mut migrations_to_create: LocalBoxStream<
+        '_,
+        anyhow::Result<(SourceRepository, ForgejoMigrateRepositoryRequest)>,
+    >,
+    run: &RunOptions,
+) -> anyhow::Result<Vec<MigrationResult>> {
+    let mut results = Vec::new();
+    let mut migrations = JoinSet::<MigrationResult>::new();
+    let mut failed = false;
+    let mut listing_error = None;
+
+    let progress = MigrationProgress::new();
+
+    loop {
+        if migrations.len() >= run.concurrency as usize {
+            if let Some(result) = migrations.join_next().await {
+                let result = result?;
+
+                failed |= result.is_failed();
+                results.push(result);
+            }
+        }
+
+        if failed && run.fail_fast {
+            break;
+        }
+
+        let (repo, options) = match migrations_to_create.try_next().await {
+            Ok(Some(migration)) => migration,
+            Ok(None) => break,
+            Err(err) => {
+                listing_error = Some(err);
+                break;
+            }
+        };
+
+        progress.add_repository();
+
+        let mut forgejo = forgejo.clone();
+        let journal = journal.clone();
+        let run = run.clone();
+        let progress = progress.clone();
+
+        migrations.spawn(async move {
+            let spinner = progress
+                .start_repository(&format!("{}/{}", &options.repo_owner, &options.repo_name));
+
+            let outcome =
+                create_journaled_migration(&mut forgejo, &journal, &repo, &options, &run).await;
+
+            progress.finish_repository(spinner);
+
+            MigrationResult::new(&options.repo_owner, &options.repo_name, outcome)
+        });
+    }
+
+    // migrations which are already running are always awaited, so their state is recorded
+    while let Some(result) = migrations.join_next().await {
+        results.push(result?);
+    }
+
+    progress.finish();
+
+    if let Some(err) = listing_error {
+        return Err(err.context(format!(
+            "Failed to list repositories after {} migrations",
+            results.len()
+        )));
+    }
+
+    if failed && run.fail_fast {
+        warn!("Stopped after the first failed migration, the remaining repositories were not attempted");
+    }
+
+    Ok(results)
+}
+
+/// Migrates the repository and records its state in the journal, repositories which the
+/// journal records as done are skipped without any request when resuming
+async fn create_journaled_migration(
+    forgejo: &mut ForgejoApi,
+    journal: &Journal,
+    repo: &SourceRepository,
+    request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<MigrationOutcome> {
+    let target = format!("{}/{}", &request.repo_owner, &request.repo_name);
+
+    if run.resume && journal.is_done(repo, &target).await {
+        info!(
+            "Repository was already migrated by a previous run: {}, skipping",
+            &target
+        );
+        return Ok(MigrationOutcome::SkippedDone);
+    }
+
+    if let Some(previous) = journal.find_moved(repo, &target).await {
+        match move_mirror(forgejo, &previous, request, run).await {
+            Ok(Some(outcome)) => {
+                if !run.dry_run {
+                    journal.forget(&previous.source, &previous.target).await?;
+                    journal
+                        .set_status(repo, &target, JournalRepositoryStatus::Done, None)
+                        .await?;
+                }
+
+                return Ok(outcome);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                journal
+                    .set_status(
+                        repo,
+                        &target,
+                        JournalRepositoryStatus::Failed,
+                        Some(err.to_string()),
+                    )
+                    .await?;
+
+                return Err(err);
+            }
+        }
+    }
+
+    let request = match resolve_repository_name(forgejo, request, run).await {
+        Ok(Ok(repo_name)) => ForgejoMigrateRepositoryRequest {
+            repo_name,
+            ..request.clone()
+        },
+        Ok(Err(conflict)) => {
+            // conflicts aren't done, so a resumed run reports them again
+            journal
+                .set_status(
+                    repo,
+                    &target,
+                    JournalRepositoryStatus::Failed,
+                    Some(conflict.clone()),
+                )
+                .await?;
+
+            return Ok(MigrationOutcome::Conflict(conflict));
+        }
+        Err(err) => {
+            journal
+                .set_status(
+                    repo,
+                    &target,
+                    JournalRepositoryStatus::Failed,
+                    Some(err.to_string()),
+                )
+                .await?;
+
+            return Err(err);
+        }
+    };
+
+    let target = format!("{}/{}", &request.repo_owner, &request.repo_name);
+
+    let previous = journal.get(repo, &target).await;
+
+    journal
+        .set_status(repo, &target, JournalRepositoryStatus::Migrating, None)
+        .await?;
+
+    let outcome = create_migration_if_not_exist(
+        forgejo,
+        &request.repo_owner,
+        repo,
+        &request,
+        previous.as_ref().map(|previous| previous.status),
+        run,
+    )
+    .await;
+
+    if let Ok(MigrationOutcome::Migrated) = &outcome {
+        set_mirror_topics(forgejo, repo, &request, run).await;
+    }
+
+    match &outcome {
+        // the repository may be migrated by someone else, so the state of the previous run is kept
+        Ok(MigrationOutcome::Conflict(_)) => match previous {
+            Some(previous) => {
+                journal
+                    .set_status(repo, &target, previous.status, previous.reason)
+                    .await?
+            }
+            None => journal.forget(&repo.clone_url, &target).await?,
+        },
+        Ok(_) => {
+            journal
+                .set_status(repo, &target, JournalRepositoryStatus::Done, None)
+                .await?
+        }
+        // forgejo may still finish a timed out migration, so it isn't recorded as failed
+        Err(err)
+            if matches!(
+                err.downcast_ref::<MigrationError>(),
+                Some(MigrationError::MigrationTimedOut(_, _))
+            ) =>
+        {
+            journal
+                .set_status(
+                    repo,
+                    &target,
+                    JournalRepositoryStatus::Migrating,
+                    Some(err.to_string()),
+                )
+                .await?
+        }
+        Err(err) => {
+            journal
+                .set_status(
+                    repo,
+                    &target,
+                    JournalRepositoryStatus::Failed,
+                    Some(err.to_string()),
+                )
+                .await?
+        }
+    }
+
+    outcome
+}
+
+/// Renames and transfers the mirror made by a previous run to follow its source repository, returns
+/// None if the mirror doesn't exist anymore or can't be moved, so a new mirror is created instead
+async fn move_mirror(
+    forgejo: &mut ForgejoApi,
+    previous: &JournalRepository,
+    request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Option<MigrationOutcome>> {
+    let Some((previous_owner, previous_name)) = previous.target.split_once('/') else {
+        return Ok(None);
+    };
+
+    let target = format!("{}/{}", &request.repo_owner, &request.repo_name);
+
+    if !forgejo
+        .repository_exists(previous_owner, previous_name)
+        .await?
+    {
+        debug!(
+            "Previous mirror {} of {} doesn't exist anymore",
+            &previous.target, &target
+        );
+        return Ok(None);
+    }
+
+    let renamed = previous_name != request.repo_name;
+    let transferred = previous_owner != request.repo_owner;
+
+    // the repository is renamed before it's transferred, so the new name must be free in both owners
+    let conflict = forgejo
+        .repository_exists(&request.repo_owner, &request.repo_name)
+        .await?
+        || (renamed
+            && transferred
+            && forgejo
+                .repository_exists(previous_owner, &request.repo_name)
+                .await?);
+
+    if conflict {
+        warn!(
+            "Repository {} was moved to {} on the source, but the new name is already taken on forgejo, keeping the previous mirror",
+            &previous.target, &target
+        );
+        return Ok(None);
+    }
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would move repository: {} -> {}",
+            &previous.target, &target
+        );
+        return Ok(Some(MigrationOutcome::Planned));
+    }
+
+    let mut mirror = None;
+
+    if renamed {
+        let rename = ForgejoEditRepositoryRequest {
+            name: Some(request.repo_name.clone()),
+            ..Default::default()
+        };
+
+        mirror = Some(
+            forgejo
+                .edit_repository(previous_owner, previous_name, &rename)
+                .await?,
+        );
+    }
+
+    if transferred {
+        mirror = Some(
+            forgejo
+                .transfer_repository(previous_owner, &request.repo_name, &request.repo_owner)
+                .await?,
+        );
+    }
+
+    info!("Repository moved: {} -> {}", &previous.target, &target);
+
+    let previous_address = mirror.map_or(previous.source.clone(), |mirror| mirror.original_url);
+
+    // forgejo's api has no way to change the remote address of a pull mirror
+    warn!(
+        "Mirror {} still pulls from {}, which only keeps working while the source redirects the previous address, change it to {} in the repository settings",
+        &target, &previous_address, &request.clone_addr
+    );
+
+    Ok(Some(MigrationOutcome::Moved(format!(
+        "moved from {}, still pulls from {} instead of {}",
+        &previous.target, &previous_address, &request.clone_addr
+    ))))
+}
+
+/// Sets the topics of a new mirror, the mirror is usable without them so a failure is only logged
+async fn set_mirror_topics(
+    forgejo: &mut ForgejoApi,
+    repo: &SourceRepository,
+    request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) {
+    let topics = run.mirror_topics(repo);
+
+    if topics.is_empty() {
+        return;
+    }
+
+    if let Err(err) = forgejo
+        .set_repository_topics(&request.repo_owner, &request.repo_name, topics)
+        .await
+    {
+        warn!(
+            "Failed to set the topics of repository {}/{}: {}",
+            &request.repo_owner, &request.repo_name, err
+        );
+    }
+}
+
+/// Finds the name under which the repository is mirrored, which is the requested name unless it's
+/// taken by another repository, then the conflict strategy decides whether the conflict is reported
+/// or an affixed name is used, returns the conflicts if no name is available
+async fn resolve_repository_name(
+    forgejo: &mut ForgejoApi,
+    request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Result<String, String>> {
+    let name = &request.repo_name;
+
+    let mut candidates = vec![name.clone()];
+
+    match run.on_conflict {
+        ConflictStrategy::Report => {}
+        ConflictStrategy::Suffix => candidates.push(format!("{}-{}", name, &run.conflict_affix)),
+        ConflictStrategy::Prefix => candidates.push(format!("{}-{}", &run.conflict_affix, name)),
+    }
+
+    let mut conflicts = Vec::new();
+
+    for candidate in candidates {
+        match find_conflict(
+            forgejo,
+            &request.repo_owner,
+            &candidate,
+            &request.clone_addr,
+        )
+        .await?
+        {
+            Some(conflict) => {
+                warn!("Repository name is taken: {}", &conflict);
+                conflicts.push(conflict);
+            }
+            None => {
+                if candidate != *name {
+                    info!(
+                        "Mirroring {} as {}/{} instead",
+                        &request.clone_addr, &request.repo_owner, &candidate
+                    );
+                }
+
+                return Ok(Ok(candidate));
+            }
+        }
+    }
+
+    Ok(Err(conflicts.join(", ")))
+}
+
+/// Describes the repository which takes the name, or returns None if the name is free or taken by a
+/// mirror of the same source repository
+async fn find_conflict(
+    forgejo: &mut ForgejoApi,
+    owner: &str,
+    name: &str,
+    clone_addr: &str,
+) -> anyhow::Result<Option<String>> {
+    if !forgejo.repository_exists(owner, name).await? {
+        return Ok(None);
+    }
+
+    let existing = forgejo.get_repository(owner, name).await?;
+
+    if !existing.mirror {
+        return Ok(Some(format!("{}/{} isn't a mirror", owner, name)));
+    }
+
+    if normalize_repository_url(&existing.original_url) != normalize_repository_url(clone_addr) {
+        return Ok(Some(format!(
+            "{}/{} mirrors {}",
+            owner, name, &existing.original_url
+        )));
+    }
+
+    Ok(None)
+}
+
+async fn create_migration_if_not_exist(
+    forgejo: &mut ForgejoApi,
+    forgejo_owner: &str,
+    repo: &SourceRepository,
+    request: &ForgejoMigrateRepositoryRequest,
+    previous_status: Option<JournalRepositoryStatus>,
+    run: &RunOptions,
+) -> anyhow::Result<MigrationOutcome> {
+    let repo_name = &request.repo_name;
+    let target = format!("{}/{}", forgejo_owner, repo_name);
+
+    if let Some(existing) = forgejo.find_repository(forgejo_owner, repo_name).await? {
+        if !is_failed_migration(&existing, repo) {
+            info!("Repository is already mirrored: {}, skipping", repo_name);
+            return Ok(MigrationOutcome::SkippedExisting);
+        }
+
+        if !is_abandoned_migration(
+            &existing,
+            previous_status,
+            run.migration_timeout,
+            SystemTime::now(),
+        ) {
+            warn!(
+                "Repository {} is empty, but its migration may still be running, skipping",
+                &target
+            );
+            return Ok(MigrationOutcome::Conflict(format!(
+                "{} is empty, its migration may still be running",
+                &target
+            )));
+        }
+
+        if run.dry_run {
+            info!(
+                "[DRY RUN] Would delete the empty repository left behind by a failed migration and migrate it again: {}",
+                &target
+            );
+            return Ok(MigrationOutcome::Planned);
+        }
+
+        warn!(
+            "Deleting the empty repository left behind by a failed migration: {}",
+            &target
+        );
+
+        forgejo.delete_repository(forgejo_owner, repo_name).await?;
+    }
+
+    if run.dry_run {
+        info!(
+            "[DRY RUN] Would migrate repository: {} into {}/{} ({})",
+            &request.clone_addr,
+            forgejo_owner,
+            repo_name,
+            describe_migration(request)
+        );
+        return Ok(MigrationOutcome::Planned);
+    }
+
+    let mut attempt = 1;
+
+    loop {
+        debug!("Migrating repository: {}", repo_name);
+
+        let err = match migrate_and_wait(forgejo, repo, request, run).await {
+            Ok(None) => break,
+            Ok(Some(conflict)) => return Ok(MigrationOutcome::Conflict(conflict)),
+            Err(err) => err,
+        };
+
+        // only migrations which forgejo finished unsuccessfully are retried, not timed out or rejected ones
+        let is_retryable = matches!(
+            err.downcast_ref::<MigrationError>(),
+            Some(MigrationError::MigrationFailed(_, _) | MigrationError::MigrationEmpty(_))
+        );
+
+        if !is_retryable || attempt >= run.migration_attempts {
+            return Err(err);
+        }
+
+        // this run saw forgejo finish the migration unsuccessfully, like a failed previous run would
+        // have recorded, anything but an empty leftover is kept
+        if let Some(existing) = forgejo.find_repository(forgejo_owner, repo_name).await? {
+            if !is_failed_migration(&existing, repo)
+                || !is_abandoned_migration(
+                    &existing,
+                    Some(JournalRepositoryStatus::Failed),
+                    run.migration_timeout,
+                    SystemTime::now(),
+                )
+            {
+                return Err(err);
+            }
+
+            warn!(
+                "{}, deleting the empty repository and retrying (attempt {}/{})",
+                err,
+                attempt + 1,
+                run.migration_attempts
+            );
+
+            forgejo.delete_repository(forgejo_owner, repo_name).await?;
+        } else {
+            warn!(
+                "{}, retrying (attempt {}/{})",
+                err,
+                attempt + 1,
+                run.migration_attempts
+            );
+        }
+
+        attempt += 1;
+    }
+
+    info!("Repository mirrored: {}", repo_name);
+
+    Ok(MigrationOutcome::Migrated)
+}
+
+/// Starts the migration and waits until forgejo finished it, which forgejo may do in a background task,
+/// returns the conflict if the repository was created by someone else in the meantime
+async fn migrate_and_wait(
+    forgejo: &mut ForgejoApi,
+    repo: &SourceRepository,
+    request: &ForgejoMigrateRepositoryRequest,
+    run: &RunOptions,
+) -> anyhow::Result<Option<String>> {
+    let target = format!("{}/{}", &request.repo_owner, &request.repo_name);
+
+    if let Err(err) = forgejo.mirror_repository(request).await {
+        // e.g. a concurrent run or another source repository mapped to the same target took the name
+        if err
+            .downcast_ref::<ForgejoApiError>()
+            .is_some_and(ForgejoApiError::is_conflict)
+        {
+            warn!(
+                "Repository {} was created by someone else, skipping",
+                &target
+            );
+            return Ok(Some(format!("{} was created concurrently", &target)));
+        }
+
+        if !forgejo
+            .repository_exists(&request.repo_owner, &request.repo_name)
+            .await?
+        {
+            return Err(err);
+        }
+
+        // the response failed, e.g. a proxy timed out, while forgejo may keep migrating in the background
+        warn!(
+            "Migration request of {} failed ({}), waiting for the migration to finish",
+            &target, err
+        );
+    }
+
+    let status = forgejo
+        .wait_for_migration(
+            &request.repo_owner,
+            &request.repo_name,
+            run.migration_timeout,
+        )
+        .await?;
+
+    match status {
+        ForgejoMigrationStatus::Finished { empty } if empty && repo.empty == Some(false) => {
+            Err(MigrationError::MigrationEmpty(target).into())
+        }
+        ForgejoMigrationStatus::Finished { .. } => Ok(None),
+        ForgejoMigrationStatus::Failed => Err(MigrationError::MigrationFailed(
+            target,
+            "forgejo deleted the repository".to_string(),
+        )
+        .into()),
+        ForgejoMigrationStatus::TimedOut => Err(MigrationError::MigrationTimedOut(
+            target,
+            humantime::format_duration(run.migration_timeout).to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Whether the repository is the empty leftover of a migration, either the migration never finished,
+/// so it has no mirror interval, or it fetched nothing although the source isn't empty
+fn is_failed_migration(existing: &ForgejoRepository, repo: &SourceRepository) -> bool {
+    existing.empty && (existing.mirror_interval.is_empty() || repo.empty == Some(false))
+}
+
+/// Whether the leftover of a migration can be deleted, i.e. a previous run saw forgejo fail the
+/// migration or the repository didn't change for longer than a migration may take, a migration
+/// still running in the background looks the same as a failed one otherwise
+fn is_abandoned_migration(
+    existing: &ForgejoRepository,
+    previous_status: Option<JournalRepositoryStatus>,
+    migration_timeout: Duration,
+    now: SystemTime,
+) -> bool {
+    if previous_status == Some(JournalRepositoryStatus::Failed) {
+        return true;
+    }
+
+    parse_timestamp(&existing.updated_at)
+        .and_then(|updated_at| now.duration_since(updated_at).ok())
+        .is_some_and(|age| age > migration_timeout)
+}
+
+fn migration_request(
+    default_options: &ForgejoMigrateRepositoryRequest,
+    repo: &SourceRepository,
+    run: &RunOptions,
+) -> ForgejoMigrateRepositoryRequest {
+    ForgejoMigrateRepositoryRequest {
+        clone_addr: repo.clone_url.clone(),
+        description: Some(mirror_description(repo)),
+        mirror_interval: run
+            .mirror_interval
+            .map(|interval| interval.resolve(repo.pushed_at.as_deref())),
+        private: default_options.private || repo.private,
+        repo_name: repo.name.clone(),
+        ..default_options.clone()
+    }
+}
+
+fn describe_migration(request: &ForgejoMigrateRepositoryRequest) -> String {
+    format!(
+        "service: {:?}, private: {}, mirror interval: {}, lfs: {}, wiki: {}, labels: {}, issues: {}, pull requests: {}, releases: {}, milestones: {}",
+        request.service,
+        request.private,
+        request.mirror_interval.as_deref().unwrap_or("default"),
+        request.lfs,
+        request.wiki,
+        request.labels,
+        request.issues,
+        request.pull_requests,
+        request.releases,
+        request.milestones
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror(original_url: &str) -> ForgejoRepository {
+        ForgejoRepository {
+            mirror: true,
+            original_url: original_url.to_string(),
+            ..ForgejoRepository::default()
+        }
+    }
+
+    fn leftover(mirror_interval: &str, updated_at: &str) -> ForgejoRepository {
+        ForgejoRepository {
+            empty: true,
+            mirror_interval: mirror_interval.to_string(),
+            updated_at: updated_at.to_string(),
+            ..mirror("https://github.com/owner/repo.git")
+        }
+    }
+
+    fn source(empty: Option<bool>) -> SourceRepository {
+        SourceRepository {
+            empty,
+            ..SourceRepository::default()
+        }
+    }
+
+    #[test]
+    fn unfinished_migrations_are_failed() {
+        let existing = leftover("", "2024-01-31T12:00:00Z");
+
+        assert!(is_failed_migration(&existing, &source(None)));
+        assert!(is_failed_migration(&existing, &source(Some(true))));
+    }
+
+    #[test]
+    fn empty_mirrors_are_only_failed_if_the_source_isnt_empty() {
+        let existing = leftover("8h0m0s", "2024-01-31T12:00:00Z");
+
+        assert!(is_failed_migration(&existing, &source(Some(false))));
+        assert!(!is_failed_migration(&existing, &source(Some(true))));
+        assert!(!is_failed_migration(&existing, &source(None)));
+    }
+
+    #[test]
+    fn mirrors_with_content_arent_failed() {
+        let existing = ForgejoRepository {
+            empty: false,
+            ..leftover("", "2024-01-31T12:00:00Z")
+        };
+
+        assert!(!is_failed_migration(&existing, &source(Some(false))));
+    }
+
+    #[test]
+    fn migrations_failed_in_a_previous_run_are_abandoned() {
+        let now = SystemTime::now();
+        let existing = leftover("", &humantime::format_rfc3339(now).to_string());
+
+        assert!(is_abandoned_migration(
+            &existing,
+            Some(JournalRepositoryStatus::Failed),
+            Duration::from_secs(30 * 60),
+            now
+        ));
+    }
+
+    #[test]
+    fn recent_migrations_may_still_be_running() {
+        let now = SystemTime::now();
+        let updated_at = humantime::format_rfc3339(now - Duration::from_secs(60)).to_string();
+        let existing = leftover("", &updated_at);
+
+        for previous_status in [None, Some(JournalRepositoryStatus::Migrating)] {
+            assert!(!is_abandoned_migration(
+                &existing,
+                previous_status,
+                Duration::from_secs(30 * 60),
+                now
+            ));
+        }
+    }
+
+    #[test]
+    fn migrations_older_than_the_timeout_are_abandoned() {
+        let now = SystemTime::now();
+        let updated_at = humantime::format_rfc3339(now - Duration::from_secs(60 * 60)).to_string();
+
+        assert!(is_abandoned_migration(
+            &leftover("", &updated_at),
+            None,
+            Duration::from_secs(30 * 60),
+            now
+        ));
+    }
+
+    #[test]
+    fn migrations_of_unknown_age_arent_abandoned() {
+        assert!(!is_abandoned_migration(
+            &leftover("", ""),
+            Some(JournalRepositoryStatus::Migrating),
+            Duration::from_secs(30 * 60),
+            SystemTime::now()
+        ));
+    }
+
+    #[test]
+    fn is_mirror_of_matches_the_host() {
+        assert!(is_mirror_of(
+            &mirror("https://github.com/owner/repo.git"),
+            "github.com"
+        ));
+        assert!(!is_mirror_of(
+            &mirror("https://gitlab.com/owner/repo.git"),
+            "github.com"
+        ));
+    }
+
+    #[test]
+    fn is_mirror_of_matches_the_port() {
+        assert!(is_mirror_of(
+            &mirror("http://localhost:8080/owner/repo.git"),
+            "localhost:8080"
+        ));
+        assert!(!is_mirror_of(
+            &mirror("http://localhost:8080/owner/repo.git"),
+            "localhost"
+        ));
+        assert!(!is_mirror_of(
+            &mirror("http://localhost/owner/repo.git"),
+            "localhost:8080"
+        ));
+        assert!(is_mirror_of(
+            &mirror("https://ghe.example.com:443/owner/repo.git"),
+            "ghe.example.com"
+        ));
+    }
+
+    #[test]
+    fn is_mirror_of_ignores_other_repositories() {
+        let repo = ForgejoRepository {
+            mirror: false,
+            ..mirror("https://github.com/owner/repo.git")
+        };
+
+        assert!(!is_mirror_of(&repo, "github.com"));
+        assert!(!is_mirror_of(&mirror("not a url"), "github.com"));
+    }
 }
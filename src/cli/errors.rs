@@ -1,18 +1,27 @@
-use std::io;
-
 use thiserror::Error;
 
-use config::errors::ConfigError;
-use forgejo::error::ForgejoApiError;
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("{0} of {1} repositories failed")]
+    RepositoriesFailed(usize, usize),
+    #[error("Migration of {0} failed: {1}")]
+    MigrationFailed(String, String),
+    #[error("Migration of {0} finished with an empty repository although its source isn't empty")]
+    MigrationEmpty(String),
+    #[error("Migration of {0} didn't finish within {1}, it may still be running")]
+    MigrationTimedOut(String, String),
+}
 
-use crate::{config, forgejo};
+#[derive(Error, Debug)]
+pub enum DeleteError {
+    #[error("Refusing to delete organisation {0}, these repositories aren't mirrors: {1}. Pass --include-non-mirrors to delete them anyway")]
+    NonMirrorRepositories(String, String),
+    #[error("Aborted, the confirmation didn't match the name {0}")]
+    ConfirmationMismatch(String),
+}
 
 #[derive(Error, Debug)]
-#[error(transparent)]
-pub enum CliError {
-    ConfigError(#[from] ConfigError),
-    ForgejoApiError(#[from] ForgejoApiError),
-    IoError(#[from] io::Error),
-    ReqwestError(#[from] reqwest::Error),
-    SerdeJsonError(#[from] serde_json::Error),
+pub enum BackupError {
+    #[error("git failed to back up repository {0}: {1}")]
+    GitFailed(String, String),
 }
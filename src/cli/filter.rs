@@ -0,0 +1,280 @@
+use std::time::SystemTime;
+
+use log::debug;
+
+use crate::cli::RepositoryFilter;
+use crate::source::models::SourceRepository;
+use crate::util::time::parse_timestamp;
+
+impl RepositoryFilter {
+    /// Whether the repository should be mirrored, the reason is logged for repositories which shouldn't
+    pub fn matches(&self, repo: &SourceRepository) -> bool {
+        match self.skip_reason(repo) {
+            Some(reason) => {
+                debug!("Skipping repository {}: {}", &repo.full_name, reason);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Returns why the repository doesn't match the filter, or None if it should be mirrored
+    fn skip_reason(&self, repo: &SourceRepository) -> Option<String> {
+        if !self.include.is_empty() && !self.include.iter().any(|re| re.is_match(&repo.name)) {
+            return Some("name doesn't match any --include pattern".to_string());
+        }
+
+        if let Some(re) = self.exclude.iter().find(|re| re.is_match(&repo.name)) {
+            return Some(format!("name matches --exclude pattern {}", re));
+        }
+
+        if self.exclude_forks && repo.fork {
+            return Some("repository is a fork".to_string());
+        }
+
+        if self.exclude_archived && repo.archived {
+            return Some("repository is archived".to_string());
+        }
+
+        if self.exclude_templates && repo.template {
+            return Some("repository is a template".to_string());
+        }
+
+        if self.exclude_disabled && repo.disabled {
+            return Some("repository is disabled".to_string());
+        }
+
+        if let Some(min_size) = self.min_size.filter(|min_size| repo.size < *min_size) {
+            return Some(format!("size {} KB is below {} KB", repo.size, min_size));
+        }
+
+        if let Some(max_size) = self.max_size.filter(|max_size| repo.size > *max_size) {
+            return Some(format!("size {} KB is above {} KB", repo.size, max_size));
+        }
+
+        if !self.languages.is_empty() {
+            let matches = repo.language.as_ref().is_some_and(|language| {
+                self.languages
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(language))
+            });
+
+            if !matches {
+                return Some(format!(
+                    "language {} is not one of the --language values",
+                    repo.language.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+
+        if !self.topics.is_empty()
+            && !repo.topics.iter().any(|topic| {
+                self.topics
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(topic))
+            })
+        {
+            return Some("repository has none of the --topic values".to_string());
+        }
+
+        if !self.source_visibilities.is_empty()
+            && !self.source_visibilities.contains(&repo.visibility)
+        {
+            return Some(format!(
+                "visibility {:?} is not one of the --source-visibility values",
+                repo.visibility
+            ));
+        }
+
+        if let Some(pushed_within) = self.pushed_within {
+            // repositories without a known push date are never filtered out by their age
+            let pushed_at = repo.pushed_at.as_deref().and_then(parse_timestamp);
+
+            let is_stale = pushed_at.is_some_and(|pushed_at| {
+                SystemTime::now()
+                    .duration_since(pushed_at)
+                    .is_ok_and(|age| age > pushed_within)
+            });
+
+            if is_stale {
+                return Some(format!(
+                    "last push at {} is older than {}",
+                    repo.pushed_at.as_deref().unwrap_or("-"),
+                    humantime::format_duration(pushed_within)
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use regex::Regex;
+
+    use super::*;
+    use crate::source::models::SourceRepositoryVisibility;
+
+    fn repo(name: &str) -> SourceRepository {
+        SourceRepository {
+            name: name.to_string(),
+            full_name: format!("owner/{}", name),
+            size: 100,
+            language: Some("Rust".to_string()),
+            topics: vec!["cli".to_string()],
+            ..SourceRepository::default()
+        }
+    }
+
+    #[test]
+    fn matches_everything_by_default() {
+        assert_eq!(RepositoryFilter::default().skip_reason(&repo("tool")), None);
+    }
+
+    #[test]
+    fn include_and_exclude_patterns() {
+        let filter = RepositoryFilter {
+            include: vec![Regex::new("^forgejo-").unwrap()],
+            exclude: vec![Regex::new("-old$").unwrap()],
+            ..RepositoryFilter::default()
+        };
+
+        assert_eq!(filter.skip_reason(&repo("forgejo-import")), None);
+        assert_eq!(
+            filter.skip_reason(&repo("gitea-import")),
+            Some("name doesn't match any --include pattern".to_string())
+        );
+        assert_eq!(
+            filter.skip_reason(&repo("forgejo-import-old")),
+            Some("name matches --exclude pattern -old$".to_string())
+        );
+    }
+
+    #[test]
+    fn excludes_forks_archived_templates_and_disabled_repositories() {
+        let filter = RepositoryFilter {
+            exclude_forks: true,
+            exclude_archived: true,
+            exclude_templates: true,
+            exclude_disabled: true,
+            ..RepositoryFilter::default()
+        };
+
+        let fork = SourceRepository {
+            fork: true,
+            ..repo("fork")
+        };
+        let archived = SourceRepository {
+            archived: true,
+            ..repo("archived")
+        };
+        let template = SourceRepository {
+            template: true,
+            ..repo("template")
+        };
+        let disabled = SourceRepository {
+            disabled: true,
+            ..repo("disabled")
+        };
+
+        assert!(filter.skip_reason(&fork).is_some());
+        assert!(filter.skip_reason(&archived).is_some());
+        assert!(filter.skip_reason(&template).is_some());
+        assert!(filter.skip_reason(&disabled).is_some());
+        assert_eq!(filter.skip_reason(&repo("plain")), None);
+    }
+
+    #[test]
+    fn size_bounds_are_inclusive() {
+        let filter = RepositoryFilter {
+            min_size: Some(100),
+            max_size: Some(200),
+            ..RepositoryFilter::default()
+        };
+
+        let size = |size| SourceRepository {
+            size,
+            ..repo("sized")
+        };
+
+        assert_eq!(filter.skip_reason(&size(100)), None);
+        assert_eq!(filter.skip_reason(&size(200)), None);
+        assert_eq!(
+            filter.skip_reason(&size(99)),
+            Some("size 99 KB is below 100 KB".to_string())
+        );
+        assert_eq!(
+            filter.skip_reason(&size(201)),
+            Some("size 201 KB is above 200 KB".to_string())
+        );
+    }
+
+    #[test]
+    fn languages_and_topics_ignore_case() {
+        let filter = RepositoryFilter {
+            languages: vec!["rust".to_string()],
+            topics: vec!["CLI".to_string()],
+            ..RepositoryFilter::default()
+        };
+
+        assert_eq!(filter.skip_reason(&repo("tool")), None);
+
+        let without_language = SourceRepository {
+            language: None,
+            ..repo("tool")
+        };
+        let other_topics = SourceRepository {
+            topics: vec!["web".to_string()],
+            ..repo("tool")
+        };
+
+        assert_eq!(
+            filter.skip_reason(&without_language),
+            Some("language - is not one of the --language values".to_string())
+        );
+        assert_eq!(
+            filter.skip_reason(&other_topics),
+            Some("repository has none of the --topic values".to_string())
+        );
+    }
+
+    #[test]
+    fn source_visibilities() {
+        let filter = RepositoryFilter {
+            source_visibilities: vec![SourceRepositoryVisibility::Internal],
+            ..RepositoryFilter::default()
+        };
+
+        let internal = SourceRepository {
+            visibility: SourceRepositoryVisibility::Internal,
+            ..repo("internal")
+        };
+
+        assert_eq!(filter.skip_reason(&internal), None);
+        assert!(filter.skip_reason(&repo("public")).is_some());
+    }
+
+    #[test]
+    fn pushed_within_keeps_repositories_without_a_known_push() {
+        let filter = RepositoryFilter {
+            pushed_within: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            ..RepositoryFilter::default()
+        };
+
+        let pushed = |pushed_at: Option<&str>| SourceRepository {
+            pushed_at: pushed_at.map(str::to_string),
+            ..repo("pushed")
+        };
+        let recently = humantime::format_rfc3339(SystemTime::now()).to_string();
+
+        assert_eq!(filter.skip_reason(&pushed(Some(&recently))), None);
+        assert_eq!(filter.skip_reason(&pushed(None)), None);
+        assert_eq!(
+            filter.skip_reason(&pushed(Some("2020-01-01T00:00:00Z"))),
+            Some("last push at 2020-01-01T00:00:00Z is older than 30days".to_string())
+        );
+    }
+}
@@ -1,9 +1,26 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
 
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use regex::Regex;
+use reqwest::StatusCode;
+
+use crate::forgejo::interval::MirrorInterval;
 use crate::forgejo::models::ForgejoVisibility;
+use crate::forgejo::topics::normalize_topics;
+use crate::github::models::{GithubAffiliation, GithubRepositoryVisibilityFilter};
+use crate::source::models::{SourceRepository, SourceRepositoryVisibility};
+use crate::util::retry::RetryPolicy;
 
+pub mod backup;
 pub mod commands;
 pub mod errors;
+pub mod filter;
+pub mod progress;
+pub mod report;
+pub mod sync;
+
+const DEFAULT_STATE_FILE: &str = "forgejo_import.state.json";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -23,10 +40,207 @@ pub enum Commands {
     /// Mirror a github repository to forgejo
     MirrorRepo(MirrorRepositoryCommand),
 
-    /// Delete a forgejo organisation including all repositories
+    /// Mirror all github repositories the token can access, including private repositories and repositories of organisations, to forgejo organisations named after their owners
+    MirrorMe(MirrorMeCommand),
+
+    /// Mirror the repositories a github user has starred to forgejo
+    MirrorStarred(MirrorStarredCommand),
+
+    /// Mirror the gists of a github user to forgejo, every gist becomes its own repository
+    MirrorGists(MirrorGistsCommand),
+
+    /// Mirror a gitlab group, including all repositories of its subgroups you have access to, to forgejo
+    MirrorGitlabGroup(MirrorGitlabGroupCommand),
+
+    /// Mirror a gitlab user, including all repositories you have access to, to forgejo
+    MirrorGitlabUser(MirrorGitlabUserCommand),
+
+    /// Mirror a gitlab repository to forgejo
+    MirrorGitlabRepo(MirrorGitlabRepositoryCommand),
+
+    /// Mirror an organisation of another gitea or forgejo instance, including all repositories you have access to, to forgejo
+    MirrorGiteaOrg(MirrorGiteaOrganisationCommand),
+
+    /// Mirror a user of another gitea or forgejo instance, including all repositories you have access to, to forgejo
+    MirrorGiteaUser(MirrorGiteaUserCommand),
+
+    /// Mirror a repository of another gitea or forgejo instance to forgejo
+    MirrorGiteaRepo(MirrorGiteaRepositoryCommand),
+
+    /// Mirror every organisation, user and repository listed in a manifest file to forgejo
+    Apply(ApplyManifestCommand),
+
+    /// Show what applying a manifest file would change on forgejo without changing anything
+    Plan(ApplyManifestCommand),
+
+    /// Update the description, website, topics, archived state, default branch and visibility of the github mirrors of a forgejo owner from their github repositories
+    Sync(SyncCommand),
+
+    /// Update the mirror interval of the mirrors of a forgejo owner
+    UpdateMirrorSettings(UpdateMirrorSettingsCommand),
+
+    /// Find the github mirrors of a forgejo owner whose github repository was deleted or renamed, and report, archive or delete them
+    Prune(PruneCommand),
+
+    /// Delete a forgejo organisation including all repositories, after confirming it by typing the name of the organisation
     DeleteOrg(DeleteForgejoOrganisationCommand),
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct RunOptions {
+    /// if set then only the changes which would be made on forgejo are shown, without changing anything
+    #[arg(long, default_value = "false")]
+    pub dry_run: bool,
+
+    /// the number of repositories which are migrated at the same time
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+
+    /// if set then no further repositories are migrated after the first failed migration, otherwise all remaining repositories are still migrated
+    #[arg(long, default_value = "false")]
+    pub fail_fast: bool,
+
+    /// if set then the state file of a previous run is used to skip the repository listing and all repositories which were already migrated
+    #[arg(long, default_value = "false")]
+    pub resume: bool,
+
+    /// the file in which the migration state of every repository is recorded, which is needed to resume a run and to find the mirrors of renamed or transferred repositories, by default forgejo_import.state.json if --resume is set, otherwise no state is recorded
+    #[arg(long)]
+    pub state_file: Option<PathBuf>,
+
+    /// how often a request is attempted before giving up on transient failures like connection errors, timeouts or retryable status codes
+    #[arg(long, default_value = "3", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: u32,
+
+    /// the delay before the first retry of a failed request, it is doubled for every further retry
+    #[arg(long, default_value = "1s", value_parser = humantime::parse_duration)]
+    pub retry_backoff: Duration,
+
+    /// the maximum delay between two retries of a failed request
+    #[arg(long, default_value = "30s", value_parser = humantime::parse_duration)]
+    pub retry_max_backoff: Duration,
+
+    /// if set then the delay between retries isn't randomized
+    #[arg(long, default_value = "false")]
+    pub no_retry_jitter: bool,
+
+    /// the http status codes of responses which are retried
+    #[arg(
+        long = "retry-status",
+        value_name = "STATUS",
+        value_delimiter = ',',
+        default_value = "502,503,504",
+        value_parser = parse_status_code
+    )]
+    pub retry_status_codes: Vec<StatusCode>,
+
+    /// how long to wait for forgejo to finish a migration which it runs in the background, independent of the timeout of the migration request itself
+    #[arg(long, default_value = "30m", value_parser = humantime::parse_duration)]
+    pub migration_timeout: Duration,
+
+    /// how often a repository is migrated before giving up if forgejo's migration fails or leaves an empty repository behind
+    #[arg(long, default_value = "2", value_parser = clap::value_parser!(u32).range(1..))]
+    pub migration_attempts: u32,
+
+    /// a topic which is added to every mirrored repository in addition to the topics of its source repository, e.g. mirror or github
+    #[arg(long = "extra-topic", value_name = "TOPIC", value_delimiter = ',')]
+    pub extra_topics: Vec<String>,
+
+    /// how often forgejo pulls the mirrors, e.g. 8h or 30m, or auto for hourly if the repository was pushed within the last week and daily otherwise, by default the interval configured in forgejo
+    #[arg(long)]
+    pub mirror_interval: Option<MirrorInterval>,
+
+    /// what happens if the name of a repository is taken on forgejo by a repository which isn't a mirror of it, the conflict is either reported or the repository is mirrored under its name with the --conflict-affix appended or prepended
+    #[arg(long, default_value = "report")]
+    pub on_conflict: ConflictStrategy,
+
+    /// the text which is appended or prepended to the names of conflicting repositories
+    #[arg(long, default_value = "mirror")]
+    pub conflict_affix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictStrategy {
+    Report,
+    Suffix,
+    Prefix,
+}
+
+impl RunOptions {
+    /// The topics of the mirror of the repository, normalized to the topics forgejo accepts
+    pub fn mirror_topics(&self, repo: &SourceRepository) -> Vec<String> {
+        normalize_topics(&[repo.topics.as_slice(), self.extra_topics.as_slice()].concat())
+    }
+
+    /// The file the migration state is recorded in, only runs which resume or were given a state file have one
+    pub fn state_file(&self) -> Option<PathBuf> {
+        self.state_file
+            .clone()
+            .or_else(|| self.resume.then(|| PathBuf::from(DEFAULT_STATE_FILE)))
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts,
+            initial_backoff: self.retry_backoff,
+            max_backoff: self.retry_max_backoff,
+            jitter: !self.no_retry_jitter,
+            retryable_status_codes: self.retry_status_codes.clone(),
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct RepositoryFilter {
+    /// only repositories whose name matches one of these regular expressions are mirrored
+    #[arg(long = "include", value_name = "REGEX", value_parser = Regex::new)]
+    pub include: Vec<Regex>,
+
+    /// repositories whose name matches one of these regular expressions are not mirrored
+    #[arg(long = "exclude", value_name = "REGEX", value_parser = Regex::new)]
+    pub exclude: Vec<Regex>,
+
+    /// if set then forks are not mirrored
+    #[arg(long, default_value = "false")]
+    pub exclude_forks: bool,
+
+    /// if set then archived repositories are not mirrored
+    #[arg(long, default_value = "false")]
+    pub exclude_archived: bool,
+
+    /// if set then template repositories are not mirrored
+    #[arg(long, default_value = "false")]
+    pub exclude_templates: bool,
+
+    /// if set then disabled repositories are not mirrored
+    #[arg(long, default_value = "false")]
+    pub exclude_disabled: bool,
+
+    /// repositories smaller than this size in kilobytes are not mirrored
+    #[arg(long, value_name = "KB")]
+    pub min_size: Option<i64>,
+
+    /// repositories larger than this size in kilobytes are not mirrored
+    #[arg(long, value_name = "KB")]
+    pub max_size: Option<i64>,
+
+    /// only repositories with one of these primary languages are mirrored
+    #[arg(long = "language", value_name = "LANGUAGE")]
+    pub languages: Vec<String>,
+
+    /// only repositories with at least one of these topics are mirrored
+    #[arg(long = "topic", value_name = "TOPIC")]
+    pub topics: Vec<String>,
+
+    /// only repositories with one of these visibilities on the source are mirrored
+    #[arg(long = "source-visibility", value_name = "VISIBILITY")]
+    pub source_visibilities: Vec<SourceRepositoryVisibility>,
+
+    /// only repositories which were pushed to within this duration are mirrored, e.g. "90days" or "1year"
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub pushed_within: Option<Duration>,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct MirrorOrganisationCommand {
     /// the url of the forgejo instance to use
@@ -41,6 +255,14 @@ pub struct MirrorOrganisationCommand {
     #[arg(long)]
     pub github_token: Option<String>,
 
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
     /// the visibility of the created forgejo organisation
     #[arg(short, long, default_value = "public")]
     pub visibility: ForgejoVisibility,
@@ -81,6 +303,12 @@ pub struct MirrorOrganisationCommand {
     #[arg(long, default_value = "false")]
     pub migrate_milestones: bool,
 
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
     /// the name of the github organisation to mirror
     pub github_organisation_name: String,
 }
@@ -99,6 +327,14 @@ pub struct MirrorUserCommand {
     #[arg(long)]
     pub github_token: Option<String>,
 
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
     /// the visibility of the created forgejo organisation
     #[arg(short, long, default_value = "public")]
     pub visibility: ForgejoVisibility,
@@ -135,6 +371,12 @@ pub struct MirrorUserCommand {
     #[arg(long, default_value = "false")]
     pub migrate_milestones: bool,
 
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
     /// the name of the github user to mirror
     pub github_user_name: String,
 }
@@ -153,6 +395,14 @@ pub struct MirrorRepositoryCommand {
     #[arg(long)]
     pub github_token: Option<String>,
 
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
     /// the name of the forgejo owner (either an user or organisation) to create the repository in
     #[arg(long)]
     pub output_owner: String,
@@ -193,12 +443,15 @@ pub struct MirrorRepositoryCommand {
     #[arg(short, long, default_value = "false")]
     pub private: bool,
 
+    #[command(flatten)]
+    pub run: RunOptions,
+
     /// the url of the repository to mirror
     pub github_repository_url: String,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct DeleteForgejoOrganisationCommand {
+pub struct MirrorMeCommand {
     /// the url of the forgejo instance to use
     #[arg(long)]
     pub forgejo_url: Option<String>,
@@ -207,6 +460,825 @@ pub struct DeleteForgejoOrganisationCommand {
     #[arg(long)]
     pub forgejo_token: Option<String>,
 
-    /// the name of the forgejo organisation to delete
-    pub forgejo_organisation_name: String,
+    /// the github token to use for obtaining information from the github api
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// only repositories the token user is related to in one of these ways are mirrored
+    #[arg(
+        long = "affiliation",
+        value_name = "AFFILIATION",
+        value_delimiter = ',',
+        default_value = "owner,collaborator,organization-member"
+    )]
+    pub affiliations: Vec<GithubAffiliation>,
+
+    /// only repositories with this visibility on github are mirrored
+    #[arg(long, default_value = "all")]
+    pub repository_visibility: GithubRepositoryVisibilityFilter,
+
+    /// the name of the forgejo organisation the repositories of a github owner are mirrored into, e.g. my-user=my-org, by default it will be the same as the github owner
+    #[arg(long, value_name = "GITHUB_OWNER=FORGEJO_ORGANISATION", value_parser = parse_owner_mapping)]
+    pub owner_map: Vec<(String, String)>,
+
+    /// the visibility of the created forgejo organisations
+    #[arg(short, long, default_value = "public")]
+    pub visibility: ForgejoVisibility,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorStarredCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for obtaining information from the github api
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// the visibility of the created forgejo organisations
+    #[arg(short, long, default_value = "public")]
+    pub visibility: ForgejoVisibility,
+
+    /// the name of the forgejo organisation to create the repositories in
+    #[arg(long, required_unless_present = "group_by_owner")]
+    pub output_organisation_name: Option<String>,
+
+    /// if set then every repository is mirrored into a forgejo organisation named after its owner on github instead of a single organisation
+    #[arg(
+        long,
+        default_value = "false",
+        conflicts_with = "output_organisation_name"
+    )]
+    pub group_by_owner: bool,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the github user whose starred repositories are mirrored, by default the user the github token belongs to
+    pub github_user_name: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGistsCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for obtaining information from the github api
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// the name of the forgejo owner (either an user or organisation) to create the repositories in
+    #[arg(long)]
+    pub output_owner: String,
+
+    /// if set then all repositories will be private, otherwise only the repositories of secret gists are private
+    #[arg(short, long, default_value = "false")]
+    pub private: bool,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the github user whose gists are mirrored, by default the user the github token belongs to, including secret gists
+    pub github_user_name: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGitlabGroupCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitlab instance to use, defaults to https://gitlab.com
+    #[arg(long)]
+    pub gitlab_url: Option<String>,
+
+    /// the gitlab token to use for obtaining information from the gitlab api
+    #[arg(long)]
+    pub gitlab_token: Option<String>,
+
+    /// the visibility of the created forgejo organisation
+    #[arg(short, long, default_value = "public")]
+    pub visibility: ForgejoVisibility,
+
+    /// the display name of the forgejo organisation to create, by default it will be the same as the gitlab group
+    #[arg(short, long)]
+    pub org_display_name: Option<String>,
+
+    /// the username of the forgejo organisation to create, by default it will be the same as the gitlab group
+    #[arg(long)]
+    pub org_username: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the full path of the gitlab group to mirror, e.g. my-group/my-subgroup
+    pub gitlab_group_path: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGitlabUserCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitlab instance to use, defaults to https://gitlab.com
+    #[arg(long)]
+    pub gitlab_url: Option<String>,
+
+    /// the gitlab token to use for obtaining information from the gitlab api
+    #[arg(long)]
+    pub gitlab_token: Option<String>,
+
+    /// the visibility of the created forgejo organisation
+    #[arg(short, long, default_value = "public")]
+    pub visibility: ForgejoVisibility,
+
+    /// the name of the forgejo organisation to create the repositories in, defaults to the name of the gitlab user
+    #[arg(long)]
+    pub output_organisation_name: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the gitlab user to mirror
+    pub gitlab_user_name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGitlabRepositoryCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitlab instance to use, defaults to https://gitlab.com
+    #[arg(long)]
+    pub gitlab_url: Option<String>,
+
+    /// the gitlab token to use for obtaining information from the gitlab api
+    #[arg(long)]
+    pub gitlab_token: Option<String>,
+
+    /// the name of the forgejo owner (either an user or organisation) to create the repository in
+    #[arg(long)]
+    pub output_owner: String,
+
+    /// the name of the forgejo repository to create, by default it will be the same as the gitlab repository
+    #[arg(long)]
+    pub output_repository_name: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    /// if set then the repository will be private, otherwise it will be public or inherit the visibility of the owner
+    #[arg(short, long, default_value = "false")]
+    pub private: bool,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the url or full path of the repository to mirror
+    pub gitlab_repository_url: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGiteaOrganisationCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_url: Option<String>,
+
+    /// the api token to use for obtaining information from the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_token: Option<String>,
+
+    /// the visibility of the created forgejo organisation, by default it will be the same as the source organisation
+    #[arg(short, long)]
+    pub visibility: Option<ForgejoVisibility>,
+
+    /// the display name of the forgejo organisation to create, by default it will be the same as the source organisation
+    #[arg(short, long)]
+    pub org_display_name: Option<String>,
+
+    /// the username of the forgejo organisation to create, by default it will be the same as the source organisation
+    #[arg(long)]
+    pub org_username: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the organisation to mirror
+    pub gitea_organisation_name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGiteaUserCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_url: Option<String>,
+
+    /// the api token to use for obtaining information from the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_token: Option<String>,
+
+    /// the visibility of the created forgejo organisation, by default it will be the same as the source user
+    #[arg(short, long)]
+    pub visibility: Option<ForgejoVisibility>,
+
+    /// the name of the forgejo organisation to create the repositories in, defaults to the name of the source user
+    #[arg(long)]
+    pub output_organisation_name: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the user to mirror
+    pub gitea_user_name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct MirrorGiteaRepositoryCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the url of the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_url: Option<String>,
+
+    /// the api token to use for obtaining information from the gitea or forgejo instance to mirror from
+    #[arg(long)]
+    pub gitea_token: Option<String>,
+
+    /// the name of the forgejo owner (either an user or organisation) to create the repository in
+    #[arg(long)]
+    pub output_owner: String,
+
+    /// the name of the forgejo repository to create, by default it will be the same as the source repository
+    #[arg(long)]
+    pub output_repository_name: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of the repositories
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    /// if set then the repository will be private, otherwise it will have the same visibility as the source repository
+    #[arg(short, long, default_value = "false")]
+    pub private: bool,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the url or owner/name of the repository to mirror
+    pub gitea_repository_url: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ApplyManifestCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for manifest entries which don't specify their own
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// the url of the gitlab instance to use for manifest entries which don't specify their own, defaults to https://gitlab.com
+    #[arg(long)]
+    pub gitlab_url: Option<String>,
+
+    /// the gitlab token to use for manifest entries which don't specify their own
+    #[arg(long)]
+    pub gitlab_token: Option<String>,
+
+    /// the url of the gitea or forgejo instance to use for manifest entries which don't specify their own
+    #[arg(long)]
+    pub gitea_url: Option<String>,
+
+    /// the gitea or forgejo token to use for manifest entries which don't specify their own
+    #[arg(long)]
+    pub gitea_token: Option<String>,
+
+    /// if set then forgejo will also migrate the L(arge) F(ile) S(torage) of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_lfs: bool,
+
+    /// if set then forgejo will also migrate the wiki of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_wiki: bool,
+
+    /// if set then forgejo will also migrate the labels of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_labels: bool,
+
+    /// if set then forgejo will also migrate the issues of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_issues: bool,
+
+    /// if set then forgejo will also migrate the pull requests of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_pull_requests: bool,
+
+    /// if set then forgejo will also migrate the releases of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_releases: bool,
+
+    /// if set then forgejo will also migrate the milestones of manifest entries which don't specify it
+    #[arg(long, default_value = "false")]
+    pub migrate_milestones: bool,
+
+    /// if set then the repositories of manifest entries which don't specify it will be private, otherwise they will be public or inherit the visibility of the entry
+    #[arg(long, default_value = "false")]
+    pub private: bool,
+
+    #[command(flatten)]
+    pub filter: RepositoryFilter,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the path to the json manifest listing the organisations, users and repositories to mirror
+    pub manifest: PathBuf,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SyncCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for obtaining information from the github api
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// if set then all mirrors are kept private, otherwise they are private if the source repository or the forgejo organisation is private
+    #[arg(long, default_value = "false")]
+    pub private: bool,
+
+    /// if set then mirrors of public repositories are made public again, otherwise the visibility is only ever changed to private, e.g. when the source repository became private
+    #[arg(long, default_value = "false")]
+    pub sync_visibility: bool,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the forgejo owner (either an user or organisation) whose mirrors are synced
+    pub forgejo_owner: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UpdateMirrorSettingsCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for obtaining the last push to the github repositories, only required for the auto mirror interval
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the forgejo owner (either an user or organisation) whose mirrors are updated
+    pub forgejo_owner: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct PruneCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// the github token to use for obtaining information from the github api
+    #[arg(long)]
+    pub github_token: Option<String>,
+
+    /// the url of the github api, e.g. https://ghe.example.com/api/v3 for a github enterprise server, by default https://api.github.com
+    #[arg(long)]
+    pub github_api_url: Option<String>,
+
+    /// the host of the github instance used in repository urls, e.g. ghe.example.com for a github enterprise server, by default github.com
+    #[arg(long)]
+    pub github_host: Option<String>,
+
+    /// the github organisation or user whose repositories are mirrored, by default the same as the forgejo owner
+    #[arg(long)]
+    pub github_owner: Option<String>,
+
+    /// what is done with orphaned mirrors, archiving keeps them as a historical copy
+    #[arg(long, default_value = "report")]
+    pub action: PruneAction,
+
+    /// if set then orphaned mirrors which are already archived are kept instead of deleted, only valid with --action delete, e.g. to delete new orphans while keeping the ones archived earlier as a historical copy
+    #[arg(long, default_value = "false")]
+    pub keep_archived: bool,
+
+    /// if set then archiving or deleting isn't confirmed by typing the name of the forgejo owner
+    #[arg(short, long, default_value = "false")]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub run: RunOptions,
+
+    /// the name of the forgejo owner (either an user or organisation) whose mirrors are pruned
+    pub forgejo_owner: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PruneAction {
+    Report,
+    Archive,
+    Delete,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct DeleteForgejoOrganisationCommand {
+    /// the url of the forgejo instance to use
+    #[arg(long)]
+    pub forgejo_url: Option<String>,
+
+    /// the api token to use for forgejo
+    #[arg(long)]
+    pub forgejo_token: Option<String>,
+
+    /// if set then the deletion isn't confirmed by typing the name of the organisation
+    #[arg(short, long, default_value = "false")]
+    pub yes: bool,
+
+    /// if set then repositories which aren't mirrors are deleted as well, otherwise the organisation isn't deleted if it contains any
+    #[arg(long, default_value = "false")]
+    pub include_non_mirrors: bool,
+
+    /// the directory to which every repository is exported as git bundle and metadata json before it is deleted
+    #[arg(long)]
+    pub backup_dir: Option<PathBuf>,
+
+    /// the name of the forgejo organisation to delete
+    pub forgejo_organisation_name: String,
+}
+
+/// Parses a http status code, only the codes of the classes 1xx to 5xx are accepted
+fn parse_status_code(status: &str) -> Result<StatusCode, String> {
+    match status.parse::<u16>() {
+        Ok(code @ 100..=599) => StatusCode::from_u16(code).map_err(|err| err.to_string()),
+        _ => Err(format!(
+            "invalid status code {}, expected a number between 100 and 599",
+            status
+        )),
+    }
+}
+
+/// Parses a `key=value` pair of an owner mapping
+fn parse_owner_mapping(mapping: &str) -> Result<(String, String), String> {
+    match mapping.split_once('=') {
+        Some((source, target)) if !source.is_empty() && !target.is_empty() => {
+            Ok((source.to_string(), target.to_string()))
+        }
+        _ => Err(format!(
+            "invalid owner mapping {}, expected GITHUB_OWNER=FORGEJO_ORGANISATION",
+            mapping
+        )),
+    }
 }
@@ -0,0 +1,93 @@
+use std::io::Write;
+use std::time::Duration;
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref MULTI_PROGRESS: MultiProgress =
+        MultiProgress::with_draw_target(if is_interactive() {
+            ProgressDrawTarget::stderr()
+        } else {
+            ProgressDrawTarget::hidden()
+        });
+}
+
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progress bars are only drawn if both stdout and stderr are terminals, otherwise only the log lines are written
+pub fn is_interactive() -> bool {
+    console::user_attended() && console::user_attended_stderr()
+}
+
+/// A log target which hides the progress bars while a log line is written, so they don't get torn apart
+pub struct ProgressLogWriter;
+
+impl Write for ProgressLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        MULTI_PROGRESS.suspend(|| std::io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
+
+/// The overall progress over all repositories of a run and a spinner for every in-flight migration,
+/// the length grows as the repositories are listed
+#[derive(Clone)]
+pub struct MigrationProgress {
+    overall: ProgressBar,
+}
+
+impl MigrationProgress {
+    pub fn new() -> Self {
+        let overall = MULTI_PROGRESS.add(ProgressBar::new(0));
+
+        overall.set_style(
+            ProgressStyle::with_template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} repositories (eta {eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+        );
+
+        if is_interactive() {
+            overall.enable_steady_tick(TICK_INTERVAL);
+        }
+
+        Self { overall }
+    }
+
+    /// Adds a listed repository to the overall progress
+    pub fn add_repository(&self) {
+        self.overall.inc_length(1);
+    }
+
+    pub fn start_repository(&self, repository: &str) -> ProgressBar {
+        let spinner = MULTI_PROGRESS.insert_before(&self.overall, ProgressBar::new_spinner());
+
+        spinner.set_style(
+            ProgressStyle::with_template("  {spinner:.cyan} {msg} [{elapsed}]").unwrap(),
+        );
+        spinner.set_message(repository.to_string());
+
+        if is_interactive() {
+            spinner.enable_steady_tick(TICK_INTERVAL);
+        }
+
+        spinner
+    }
+
+    pub fn finish_repository(&self, spinner: ProgressBar) {
+        spinner.finish_and_clear();
+        MULTI_PROGRESS.remove(&spinner);
+
+        self.overall.inc(1);
+    }
+
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+        MULTI_PROGRESS.remove(&self.overall);
+    }
+}
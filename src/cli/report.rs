@@ -0,0 +1,261 @@
+use console::style;
+
+use crate::cli::errors::MigrationError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationOutcome {
+    Migrated,
+    SkippedExisting,
+    /// the repository was already migrated by a previous run according to the state file
+    SkippedDone,
+    Planned,
+    /// the metadata of an existing mirror was updated, with the names of the changed fields
+    Updated(String),
+    /// the mirror was renamed or transferred after its source moved, it still pulls from the previous
+    /// address of the source since forgejo can't change it, with the previous and the new address
+    Moved(String),
+    Unchanged,
+    /// the source of the mirror disappeared, with the reason
+    Orphaned(String),
+    /// the mirror was archived because its source disappeared
+    Archived(String),
+    /// the mirror was deleted because its source disappeared
+    Deleted(String),
+    /// the name of the repository is taken by another repository on forgejo
+    Conflict(String),
+    Failed(String),
+}
+
+impl MigrationOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            MigrationOutcome::Migrated => "created",
+            MigrationOutcome::SkippedExisting => "skipped (exists)",
+            MigrationOutcome::SkippedDone => "skipped (done)",
+            MigrationOutcome::Planned => "planned",
+            MigrationOutcome::Updated(_) => "updated",
+            MigrationOutcome::Moved(_) => "moved",
+            MigrationOutcome::Unchanged => "unchanged",
+            MigrationOutcome::Orphaned(_) => "orphaned",
+            MigrationOutcome::Archived(_) => "archived",
+            MigrationOutcome::Deleted(_) => "deleted",
+            MigrationOutcome::Conflict(_) => "conflict",
+            MigrationOutcome::Failed(_) => "failed",
+        }
+    }
+}
+
+/// The outcome of migrating a single repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationResult {
+    pub repository: String,
+    pub outcome: MigrationOutcome,
+}
+
+impl MigrationResult {
+    pub fn new(
+        forgejo_owner: &str,
+        repo_name: &str,
+        outcome: anyhow::Result<MigrationOutcome>,
+    ) -> Self {
+        MigrationResult {
+            repository: format!("{}/{}", forgejo_owner, repo_name),
+            outcome: outcome.unwrap_or_else(|err| MigrationOutcome::Failed(err.to_string())),
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.outcome, MigrationOutcome::Failed(_))
+    }
+}
+
+/// The number of results with each outcome, skipped repositories are counted together
+#[derive(Debug, Default, PartialEq)]
+struct Tally {
+    created: usize,
+    skipped: usize,
+    planned: usize,
+    updated: usize,
+    moved: usize,
+    unchanged: usize,
+    orphaned: usize,
+    archived: usize,
+    deleted: usize,
+    conflicts: usize,
+    failed: usize,
+}
+
+impl Tally {
+    fn count(results: &[MigrationResult]) -> Self {
+        let mut tally = Tally::default();
+
+        for result in results {
+            let count = match result.outcome {
+                MigrationOutcome::Migrated => &mut tally.created,
+                MigrationOutcome::SkippedExisting | MigrationOutcome::SkippedDone => {
+                    &mut tally.skipped
+                }
+                MigrationOutcome::Planned => &mut tally.planned,
+                MigrationOutcome::Updated(_) => &mut tally.updated,
+                MigrationOutcome::Moved(_) => &mut tally.moved,
+                MigrationOutcome::Unchanged => &mut tally.unchanged,
+                MigrationOutcome::Orphaned(_) => &mut tally.orphaned,
+                MigrationOutcome::Archived(_) => &mut tally.archived,
+                MigrationOutcome::Deleted(_) => &mut tally.deleted,
+                MigrationOutcome::Conflict(_) => &mut tally.conflicts,
+                MigrationOutcome::Failed(_) => &mut tally.failed,
+            };
+
+            *count += 1;
+        }
+
+        tally
+    }
+}
+
+/// Prints a summary table of all results and fails if any repository failed to migrate
+pub fn report_results(results: &[MigrationResult]) -> anyhow::Result<()> {
+    if results.is_empty() {
+        println!("No repositories to migrate");
+        return Ok(());
+    }
+
+    let mut sorted = results.iter().collect::<Vec<&MigrationResult>>();
+    sorted.sort_by_key(|result| (result.is_failed(), result.repository.clone()));
+
+    let status_width = sorted
+        .iter()
+        .map(|result| result.outcome.label().len())
+        .max()
+        .unwrap_or_default()
+        .max("STATUS".len());
+    let repository_width = sorted
+        .iter()
+        .map(|result| result.repository.len())
+        .max()
+        .unwrap_or_default()
+        .max("REPOSITORY".len());
+
+    println!();
+    println!(
+        "{:<status_width$}  {:<repository_width$}  DETAILS",
+        "STATUS", "REPOSITORY"
+    );
+
+    for result in &sorted {
+        let label = format!("{:<status_width$}", result.outcome.label());
+
+        let (label, details) = match &result.outcome {
+            MigrationOutcome::Migrated => (style(label).green(), ""),
+            MigrationOutcome::SkippedExisting => (style(label).yellow(), ""),
+            MigrationOutcome::SkippedDone => (style(label).yellow(), ""),
+            MigrationOutcome::Planned => (style(label).cyan(), ""),
+            MigrationOutcome::Updated(fields) => (style(label).green(), fields.as_str()),
+            MigrationOutcome::Moved(details) => (style(label).yellow(), details.as_str()),
+            MigrationOutcome::Unchanged => (style(label).dim(), ""),
+            MigrationOutcome::Orphaned(reason) => (style(label).yellow(), reason.as_str()),
+            MigrationOutcome::Archived(reason) => (style(label).green(), reason.as_str()),
+            MigrationOutcome::Deleted(reason) => (style(label).green(), reason.as_str()),
+            MigrationOutcome::Conflict(conflict) => (style(label).yellow(), conflict.as_str()),
+            MigrationOutcome::Failed(err) => (style(label).red(), err.as_str()),
+        };
+
+        println!(
+            "{}  {:<repository_width$}  {}",
+            label,
+            result.repository,
+            details.replace('\n', " ")
+        );
+    }
+
+    let tally = Tally::count(results);
+
+    let mut counts = vec![format!("{} created", tally.created)];
+
+    // only runs which sync existing mirrors update or leave repositories unchanged
+    if tally.updated > 0 || tally.unchanged > 0 {
+        counts.push(format!("{} updated", tally.updated));
+        counts.push(format!("{} unchanged", tally.unchanged));
+    }
+
+    // only prune runs find orphaned mirrors
+    if tally.orphaned > 0 || tally.archived > 0 || tally.deleted > 0 {
+        counts.push(format!("{} orphaned", tally.orphaned));
+        counts.push(format!("{} archived", tally.archived));
+        counts.push(format!("{} deleted", tally.deleted));
+    }
+
+    if tally.moved > 0 {
+        counts.push(format!("{} moved", tally.moved));
+    }
+
+    counts.push(format!("{} planned", tally.planned));
+    counts.push(format!("{} skipped", tally.skipped));
+
+    if tally.conflicts > 0 {
+        counts.push(format!("{} conflicts", tally.conflicts));
+    }
+
+    counts.push(format!("{} failed", tally.failed));
+
+    println!();
+    println!("{} repositories: {}", results.len(), counts.join(", "));
+
+    if tally.moved > 0 {
+        println!(
+            "{}",
+            style(format!(
+                "{} moved mirrors still pull from the previous address of their source, change it in their repository settings",
+                tally.moved
+            ))
+            .yellow()
+        );
+    }
+
+    if tally.failed > 0 {
+        return Err(MigrationError::RepositoriesFailed(tally.failed, results.len()).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(outcome: MigrationOutcome) -> MigrationResult {
+        MigrationResult {
+            repository: String::from("owner/repository"),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn tally_counts_every_outcome_once() {
+        let results = [
+            result(MigrationOutcome::Migrated),
+            result(MigrationOutcome::Migrated),
+            result(MigrationOutcome::SkippedExisting),
+            result(MigrationOutcome::SkippedDone),
+            result(MigrationOutcome::Updated(String::from("description"))),
+            result(MigrationOutcome::Moved(String::from(
+                "moved from owner/old",
+            ))),
+            result(MigrationOutcome::Conflict(String::from("taken"))),
+            result(MigrationOutcome::Failed(String::from("error"))),
+        ];
+
+        assert_eq!(
+            Tally::count(&results),
+            Tally {
+                created: 2,
+                skipped: 2,
+                updated: 1,
+                moved: 1,
+                conflicts: 1,
+                failed: 1,
+                ..Tally::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::forgejo::models::{ForgejoEditRepositoryRequest, ForgejoRepository};
+use crate::source::models::SourceRepository;
+
+/// The metadata of a mirror which differs from the metadata of its source repository
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct MetadataChanges {
+    pub edit: ForgejoEditRepositoryRequest,
+    /// the new topics, or None if the topics are unchanged
+    pub topics: Option<Vec<String>>,
+    /// the names of all changed fields
+    pub fields: Vec<&'static str>,
+}
+
+impl MetadataChanges {
+    /// Compares the mirror to its source repository, the mirror is private if `private` is set and has
+    /// the already normalized `topics`
+    pub fn between(
+        mirror: &ForgejoRepository,
+        source: &SourceRepository,
+        private: bool,
+        topics: Vec<String>,
+    ) -> Self {
+        let mut changes = MetadataChanges::default();
+
+        let description = mirror_description(source);
+        if mirror.description != description {
+            changes.edit.description = Some(description);
+            changes.fields.push("description");
+        }
+
+        let website = source.homepage.clone().unwrap_or_default();
+        if mirror.website != website {
+            changes.edit.website = Some(website);
+            changes.fields.push("website");
+        }
+
+        if mirror.archived != source.archived {
+            changes.edit.archived = Some(source.archived);
+            changes.fields.push("archived");
+        }
+
+        if let Some(default_branch) = source
+            .default_branch
+            .clone()
+            .filter(|branch| !branch.is_empty() && *branch != mirror.default_branch)
+        {
+            changes.edit.default_branch = Some(default_branch);
+            changes.fields.push("default branch");
+        }
+
+        if mirror.private != private {
+            changes.edit.private = Some(private);
+            changes.fields.push("visibility");
+        }
+
+        let mut mirror_topics = mirror.topics.clone().unwrap_or_default();
+        let mut sorted_topics = topics.clone();
+
+        mirror_topics.sort();
+        sorted_topics.sort();
+
+        if mirror_topics != sorted_topics {
+            changes.topics = Some(topics);
+            changes.fields.push("topics");
+        }
+
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn has_edits(&self) -> bool {
+        self.edit != ForgejoEditRepositoryRequest::default()
+    }
+}
+
+/// The description of the mirror of a repository
+pub fn mirror_description(repo: &SourceRepository) -> String {
+    format!("[MIRROR] {}", repo.description.clone().unwrap_or_default())
+}
@@ -4,4 +4,6 @@ use thiserror::Error;
 pub enum ConfigError {
     #[error("You didn't specify the argument {0} in either the CLI or the config file. Please specify it in one of those places.")]
     MissingRequiredArgument(String),
+    #[error("The argument {0} can only be used together with {1}.")]
+    ArgumentRequires(String, String),
 }
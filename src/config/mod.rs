@@ -5,14 +5,16 @@ use tokio::fs;
 
 use errors::ConfigError;
 
-use crate::cli::Commands;
+use crate::cli::{Commands, PruneAction};
 use crate::config::constants::{
-    CONFIG_FILE_NAME, CONFIG_PATH, HOME_ENV, PROJECT_NAME, WINDOWS_APPDATA_ENV,
-    XDG_CONFIG_ENV,
+    CONFIG_FILE_NAME, CONFIG_PATH, HOME_ENV, PROJECT_NAME, WINDOWS_APPDATA_ENV, XDG_CONFIG_ENV,
 };
 #[cfg(windows)]
-use crate::config::constants::{WINDOWS_HOMEDRIVE_ENV, WINDOWS_HOMEPATH_ENV, WINDOWS_USERPROFILE_ENV};
+use crate::config::constants::{
+    WINDOWS_HOMEDRIVE_ENV, WINDOWS_HOMEPATH_ENV, WINDOWS_USERPROFILE_ENV,
+};
 use crate::config::models::ForgejoImportConfig;
+use crate::forgejo::interval::MirrorInterval;
 
 mod constants;
 pub(crate) mod errors;
@@ -39,6 +41,14 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                     cmd.github_token = config.github_token;
                 }
 
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
                 if let Some(migrate_wiki) = config.migrate_wiki {
                     cmd.migrate_wiki = migrate_wiki;
                 }
@@ -46,6 +56,10 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                 if let Some(migrate_lfs) = config.migrate_lfs {
                     cmd.migrate_lfs = migrate_lfs;
                 }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
             }
             Commands::MirrorUser(cmd) => {
                 if cmd.forgejo_url.is_none() {
@@ -60,6 +74,14 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                     cmd.github_token = config.github_token;
                 }
 
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
                 if let Some(migrate_wiki) = config.migrate_wiki {
                     cmd.migrate_wiki = migrate_wiki;
                 }
@@ -67,6 +89,10 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                 if let Some(migrate_lfs) = config.migrate_lfs {
                     cmd.migrate_lfs = migrate_lfs;
                 }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
             }
             Commands::MirrorRepo(cmd) => {
                 if cmd.forgejo_url.is_none() {
@@ -81,6 +107,80 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                     cmd.github_token = config.github_token;
                 }
 
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorMe(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorStarred(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
                 if let Some(migrate_wiki) = config.migrate_wiki {
                     cmd.migrate_wiki = migrate_wiki;
                 }
@@ -88,6 +188,325 @@ pub async fn apply_config(command: &mut Commands) -> anyhow::Result<()> {
                 if let Some(migrate_lfs) = config.migrate_lfs {
                     cmd.migrate_lfs = migrate_lfs;
                 }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGists(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGitlabGroup(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitlab_url.is_none() {
+                    cmd.gitlab_url = config.gitlab_url;
+                }
+
+                if cmd.gitlab_token.is_none() {
+                    cmd.gitlab_token = config.gitlab_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGitlabUser(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitlab_url.is_none() {
+                    cmd.gitlab_url = config.gitlab_url;
+                }
+
+                if cmd.gitlab_token.is_none() {
+                    cmd.gitlab_token = config.gitlab_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGitlabRepo(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitlab_url.is_none() {
+                    cmd.gitlab_url = config.gitlab_url;
+                }
+
+                if cmd.gitlab_token.is_none() {
+                    cmd.gitlab_token = config.gitlab_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGiteaOrg(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitea_url.is_none() {
+                    cmd.gitea_url = config.gitea_url;
+                }
+
+                if cmd.gitea_token.is_none() {
+                    cmd.gitea_token = config.gitea_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGiteaUser(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitea_url.is_none() {
+                    cmd.gitea_url = config.gitea_url;
+                }
+
+                if cmd.gitea_token.is_none() {
+                    cmd.gitea_token = config.gitea_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::MirrorGiteaRepo(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.gitea_url.is_none() {
+                    cmd.gitea_url = config.gitea_url;
+                }
+
+                if cmd.gitea_token.is_none() {
+                    cmd.gitea_token = config.gitea_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::Apply(cmd) | Commands::Plan(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
+                if cmd.gitlab_url.is_none() {
+                    cmd.gitlab_url = config.gitlab_url;
+                }
+
+                if cmd.gitlab_token.is_none() {
+                    cmd.gitlab_token = config.gitlab_token;
+                }
+
+                if cmd.gitea_url.is_none() {
+                    cmd.gitea_url = config.gitea_url;
+                }
+
+                if cmd.gitea_token.is_none() {
+                    cmd.gitea_token = config.gitea_token;
+                }
+
+                if let Some(migrate_wiki) = config.migrate_wiki {
+                    cmd.migrate_wiki = migrate_wiki;
+                }
+
+                if let Some(migrate_lfs) = config.migrate_lfs {
+                    cmd.migrate_lfs = migrate_lfs;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::Sync(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+            }
+            Commands::UpdateMirrorSettings(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
+
+                if cmd.run.mirror_interval.is_none() {
+                    cmd.run.mirror_interval = config.mirror_interval;
+                }
+            }
+            Commands::Prune(cmd) => {
+                if cmd.forgejo_url.is_none() {
+                    cmd.forgejo_url = config.forgejo_url;
+                }
+
+                if cmd.forgejo_token.is_none() {
+                    cmd.forgejo_token = config.forgejo_token;
+                }
+
+                if cmd.github_token.is_none() {
+                    cmd.github_token = config.github_token;
+                }
+
+                if cmd.github_api_url.is_none() {
+                    cmd.github_api_url = config.github_api_url;
+                }
+
+                if cmd.github_host.is_none() {
+                    cmd.github_host = config.github_host;
+                }
             }
             Commands::DeleteOrg(cmd) => {
                 if cmd.forgejo_url.is_none() {
@@ -159,6 +578,242 @@ fn validate_cmd(cmd: &Commands) -> anyhow::Result<()> {
                 );
             }
         }
+        Commands::MirrorMe(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorStarred(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorGists(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorGitlabGroup(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitlab_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("gitlab-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorGitlabUser(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitlab_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("gitlab-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorGitlabRepo(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitlab_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("gitlab-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::MirrorGiteaOrg(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitea_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-url".to_string()).into());
+            }
+
+            if cmd.gitea_token.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-token".to_string()).into());
+            }
+        }
+        Commands::MirrorGiteaUser(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitea_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-url".to_string()).into());
+            }
+
+            if cmd.gitea_token.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-token".to_string()).into());
+            }
+        }
+        Commands::MirrorGiteaRepo(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.gitea_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-url".to_string()).into());
+            }
+
+            if cmd.gitea_token.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("gitea-token".to_string()).into());
+            }
+        }
+        Commands::Apply(cmd) | Commands::Plan(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::Sync(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::UpdateMirrorSettings(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.run.mirror_interval.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("mirror-interval".to_string()).into(),
+                );
+            }
+
+            // the automatic interval depends on the last push to the github repository
+            if cmd.run.mirror_interval == Some(MirrorInterval::Auto) && cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+        }
+        Commands::Prune(cmd) => {
+            if cmd.forgejo_url.is_none() {
+                return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
+            }
+
+            if cmd.forgejo_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("forgejo-token".to_string()).into(),
+                );
+            }
+
+            if cmd.github_token.is_none() {
+                return Err(
+                    ConfigError::MissingRequiredArgument("github-token".to_string()).into(),
+                );
+            }
+
+            if cmd.keep_archived && cmd.action != PruneAction::Delete {
+                return Err(ConfigError::ArgumentRequires(
+                    "keep-archived".to_string(),
+                    "action delete".to_string(),
+                )
+                .into());
+            }
+        }
         Commands::DeleteOrg(cmd) => {
             if cmd.forgejo_url.is_none() {
                 return Err(ConfigError::MissingRequiredArgument("forgejo-url".to_string()).into());
@@ -225,7 +880,7 @@ fn search_config_in_default_locations() -> anyhow::Result<Option<PathBuf>> {
 }
 
 fn get_config_path_from_default_location_by_env(env: &str) -> Option<PathBuf> {
-    let env_resolved = env::var(env).unwrap_or(String::new());
+    let env_resolved = env::var(env).unwrap_or_default();
 
     if !env_resolved.is_empty() {
         let env_config_home = PathBuf::from(env_resolved)
@@ -1,11 +1,20 @@
 use serde_derive::{Deserialize, Serialize};
 
+use crate::forgejo::interval::MirrorInterval;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ForgejoImportConfig {
     pub forgejo_url: Option<String>,
     pub forgejo_token: Option<String>,
     pub github_token: Option<String>,
+    pub github_api_url: Option<String>,
+    pub github_host: Option<String>,
+    pub gitlab_url: Option<String>,
+    pub gitlab_token: Option<String>,
+    pub gitea_url: Option<String>,
+    pub gitea_token: Option<String>,
     pub migrate_wiki: Option<bool>,
     pub migrate_lfs: Option<bool>,
+    pub mirror_interval: Option<MirrorInterval>,
 }
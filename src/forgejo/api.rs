@@ -1,30 +1,53 @@
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::{anyhow, Error};
+use anyhow::Error;
 use base64::{engine::general_purpose, Engine as _};
 use bytes::Bytes;
+use futures_util::stream::{self, LocalBoxStream};
+use futures_util::{StreamExt, TryStreamExt};
 use general_purpose::STANDARD;
+use log::{debug, warn};
 use reqwest::header::HeaderMap;
 use reqwest::{Client, Method, Request, Response, StatusCode, Url};
 use serde::de::DeserializeOwned;
+use tokio::sync::{Mutex, OnceCell};
+use tokio::time::sleep;
 use tower::limit::RateLimit;
+use tower::timeout::error::Elapsed;
 use tower::timeout::Timeout;
-use tower::{Service, ServiceExt};
+use tower::{BoxError, Service, ServiceExt};
 
 use crate::forgejo::error::ForgejoApiError;
 use crate::forgejo::models::{
-    ForgejoCreateOrganisationRequest, ForgejoGetOrganisationRepositoriesResponse,
-    ForgejoMigrateRepositoryRequest, ForgejoRepository, ForgejoUpdateUserAvatarRequest,
+    ForgejoApiSettings, ForgejoCreateOrganisationRequest, ForgejoEditRepositoryRequest,
+    ForgejoMigrateRepositoryRequest, ForgejoMigrationStatus, ForgejoOrganisation, ForgejoOwner,
+    ForgejoRepository, ForgejoRepositoryTopicsRequest, ForgejoTransferRepositoryRequest,
+    ForgejoUpdateUserAvatarRequest,
 };
-use crate::util::http::{CLIENT, USER_AGENT};
+use crate::util::http::{paginate, PageClient, CLIENT, USER_AGENT};
+use crate::util::retry::RetryPolicy;
 
 const API_VERSION: &str = "1";
 
+/// the default of `MAX_RESPONSE_ITEMS`, used if the instance doesn't expose its api settings
+const DEFAULT_MAX_PAGE_SIZE: usize = 50;
+
+/// the delay before the state of a running migration is checked again, it is doubled up to the maximum
+const MIGRATION_POLL_INITIAL_INTERVAL: Duration = Duration::from_secs(1);
+const MIGRATION_POLL_MAX_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Clone)]
 pub(crate) struct ForgejoApi {
+    api_key: String,
     client: Client,
     headers: HeaderMap,
-    service: Timeout<RateLimit<Client>>,
+    /// shared between all clones so concurrent requests still respect the rate limit
+    service: Arc<Mutex<Timeout<RateLimit<Client>>>>,
     base_url: String,
+    retry_policy: RetryPolicy,
+    /// fetched once and shared between all clones
+    max_page_size: Arc<OnceCell<usize>>,
 }
 
 impl ForgejoApi {
@@ -43,13 +66,29 @@ impl ForgejoApi {
             .service(client.clone());
 
         Ok(ForgejoApi {
+            api_key,
             client,
             headers,
-            service,
+            service: Arc::new(Mutex::new(service)),
             base_url,
+            retry_policy: RetryPolicy::default(),
+            max_page_size: Arc::new(OnceCell::new()),
         })
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     pub async fn create_organization(
         &mut self,
         options: &mut ForgejoCreateOrganisationRequest,
@@ -150,40 +189,162 @@ impl ForgejoApi {
         Ok(())
     }
 
-    pub async fn get_organisation_repositories(
+    pub fn get_organisation_repositories<'a>(
+        &'a mut self,
+        name: &'a str,
+    ) -> LocalBoxStream<'a, anyhow::Result<ForgejoRepository>> {
+        self.get_paginated_repositories(format!(
+            "{}/api/v{}/orgs/{}/repos",
+            &self.base_url, API_VERSION, name
+        ))
+    }
+
+    pub async fn get_organisation(&mut self, name: &str) -> anyhow::Result<ForgejoOrganisation> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                Url::parse(&format!(
+                    "{}/api/v{}/orgs/{}",
+                    &self.base_url, API_VERSION, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<ForgejoOrganisation>(req)
+            .await
+    }
+
+    pub async fn get_organisation_avatar(&mut self, name: &str) -> anyhow::Result<Bytes> {
+        let org = self.get_organisation(name).await?;
+
+        self.get_avatar(&org.avatar_url).await
+    }
+
+    pub async fn get_user(&mut self, name: &str) -> anyhow::Result<ForgejoOwner> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                Url::parse(&format!(
+                    "{}/api/v{}/users/{}",
+                    &self.base_url, API_VERSION, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<ForgejoOwner>(req)
+            .await
+    }
+
+    pub async fn get_user_avatar(&mut self, name: &str) -> anyhow::Result<Bytes> {
+        let user = self.get_user(name).await?;
+
+        self.get_avatar(&user.avatar_url).await
+    }
+
+    pub fn get_user_repositories<'a>(
+        &'a mut self,
+        name: &'a str,
+    ) -> LocalBoxStream<'a, anyhow::Result<ForgejoRepository>> {
+        self.get_paginated_repositories(format!(
+            "{}/api/v{}/users/{}/repos",
+            &self.base_url, API_VERSION, name
+        ))
+    }
+
+    fn get_paginated_repositories(
+        &mut self,
+        url: String,
+    ) -> LocalBoxStream<'_, anyhow::Result<ForgejoRepository>> {
+        // the page size is only known after asking the instance for its limits
+        stream::once(async move {
+            let page_size = self.get_max_page_size().await;
+
+            anyhow::Ok(paginate(self, &format!("{}?limit={}", url, page_size)))
+        })
+        .try_flatten()
+        .boxed_local()
+    }
+
+    pub async fn get_api_settings(&mut self) -> anyhow::Result<ForgejoApiSettings> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                Url::parse(&format!(
+                    "{}/api/v{}/settings/api",
+                    &self.base_url, API_VERSION
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<ForgejoApiSettings>(req)
+            .await
+    }
+
+    /// The largest page size the instance allows, the instance silently caps larger page sizes
+    async fn get_max_page_size(&mut self) -> usize {
+        let max_page_size = self.max_page_size.clone();
+
+        *max_page_size
+            .get_or_init(|| async {
+                match self.get_api_settings().await {
+                    Ok(settings) if settings.max_response_items > 0 => settings.max_response_items,
+                    Ok(_) => DEFAULT_MAX_PAGE_SIZE,
+                    Err(err) => {
+                        debug!(
+                            "Failed to fetch the api settings of the forgejo instance, using a page size of {}: {}",
+                            DEFAULT_MAX_PAGE_SIZE, err
+                        );
+                        DEFAULT_MAX_PAGE_SIZE
+                    }
+                }
+            })
+            .await
+    }
+
+    pub async fn get_repository(
         &mut self,
+        owner: &str,
         name: &str,
-    ) -> anyhow::Result<Vec<ForgejoRepository>> {
-        let mut repos = Vec::new();
-
-        let mut index = 1;
-        let mut last_response_count = 0;
-
-        while index == 1 || last_response_count > 0 {
-            let req = self
-                .client
-                .request(
-                    Method::GET,
-                    Url::parse(&format!(
-                        "{}/api/v{}/orgs/{}/repos",
-                        &self.base_url, API_VERSION, name
-                    ))?,
-                )
-                .query(&[("page", index), ("per_page", 25)])
-                .headers(self.headers.clone())
-                .build()?;
-
-            let res = self
-                .do_request_handle_status_parsed::<ForgejoGetOrganisationRepositoriesResponse>(req)
-                .await?;
-
-            index += 1;
-            last_response_count = res.len();
-
-            repos.extend(res);
-        }
+    ) -> anyhow::Result<ForgejoRepository> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                Url::parse(&format!(
+                    "{}/api/v{}/repos/{}/{}",
+                    &self.base_url, API_VERSION, owner, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .build()?;
 
-        Ok(repos)
+        self.do_request_handle_status_parsed::<ForgejoRepository>(req)
+            .await
+    }
+
+    /// Fetches the repository, or returns None if it doesn't exist
+    pub async fn find_repository(
+        &mut self,
+        owner: &str,
+        name: &str,
+    ) -> anyhow::Result<Option<ForgejoRepository>> {
+        match self.get_repository(owner, name).await {
+            Ok(repo) => Ok(Some(repo)),
+            Err(err)
+                if err
+                    .downcast_ref::<ForgejoApiError>()
+                    .is_some_and(ForgejoApiError::is_not_found) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
     }
 
     pub async fn mirror_repository(
@@ -203,9 +364,100 @@ impl ForgejoApi {
             .json(options)
             .build()?;
 
-        self.do_request_handle_status(req).await?;
+        let mut attempt = 1;
 
-        Ok(())
+        loop {
+            let req = req
+                .try_clone()
+                .ok_or(ForgejoApiError::RequestNotRetryable)?;
+
+            let err = match self.do_request_handle_status(req).await {
+                Ok(_) => return Ok(()),
+                Err(err) => err,
+            };
+
+            let is_retryable = match err.downcast_ref::<ForgejoApiError>() {
+                Some(ForgejoApiError::NoSuccessStatusCodeError(status, _)) => {
+                    self.retry_policy.is_retryable_status(*status)
+                }
+                _ => self.retry_policy.is_retryable_error(&err),
+            };
+
+            if !is_retryable || !self.retry_policy.can_retry(attempt) {
+                return Err(err);
+            }
+
+            let backoff = self.retry_policy.backoff(attempt);
+
+            warn!(
+                "Migration of {}/{} failed ({}), retrying in {}ms (attempt {}/{})",
+                &options.repo_owner,
+                &options.repo_name,
+                err,
+                backoff.as_millis(),
+                attempt + 1,
+                self.retry_policy.max_attempts
+            );
+
+            sleep(backoff).await;
+
+            // the migration isn't idempotent, it may have been created even though the response failed
+            if self
+                .repository_exists(&options.repo_owner, &options.repo_name)
+                .await?
+            {
+                warn!(
+                    "Repository {}/{} was created by the failed migration attempt, not retrying",
+                    &options.repo_owner, &options.repo_name
+                );
+
+                return Ok(());
+            }
+
+            attempt += 1;
+        }
+    }
+
+    /// Polls the repository until forgejo finished migrating it. The api doesn't expose the state of
+    /// the migration task, so it's derived from the repository: forgejo creates the repository before
+    /// cloning it, but only creates the mirror entry holding the mirror interval once the clone
+    /// succeeded, so a mirror which is empty and has no interval is still being migrated. Forgejo
+    /// deletes the repository if the migration fails.
+    pub async fn wait_for_migration(
+        &mut self,
+        owner: &str,
+        name: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<ForgejoMigrationStatus> {
+        let started = Instant::now();
+        let mut interval = MIGRATION_POLL_INITIAL_INTERVAL;
+
+        loop {
+            let Some(repo) = self.find_repository(owner, name).await? else {
+                return Ok(ForgejoMigrationStatus::Failed);
+            };
+
+            if !repo.empty || !repo.mirror_interval.is_empty() {
+                return Ok(ForgejoMigrationStatus::Finished { empty: repo.empty });
+            }
+
+            let elapsed = started.elapsed();
+
+            if elapsed >= timeout {
+                return Ok(ForgejoMigrationStatus::TimedOut);
+            }
+
+            debug!(
+                "Migration of {}/{} is still running after {}s",
+                owner,
+                name,
+                elapsed.as_secs()
+            );
+
+            sleep(interval.min(timeout - elapsed)).await;
+
+            interval = (interval * 2).min(MIGRATION_POLL_MAX_INTERVAL);
+        }
     }
 
     pub async fn repository_exists(&mut self, owner: &str, name: &str) -> anyhow::Result<bool> {
@@ -235,6 +487,80 @@ impl ForgejoApi {
         Ok(status != StatusCode::NOT_FOUND)
     }
 
+    pub async fn edit_repository(
+        &mut self,
+        owner: &str,
+        name: &str,
+        request: &ForgejoEditRepositoryRequest,
+    ) -> anyhow::Result<ForgejoRepository> {
+        let req = self
+            .client
+            .request(
+                Method::PATCH,
+                Url::parse(&format!(
+                    "{}/api/v{}/repos/{}/{}",
+                    &self.base_url, API_VERSION, owner, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .json(request)
+            .build()?;
+
+        self.do_request_handle_status_parsed::<ForgejoRepository>(req)
+            .await
+    }
+
+    /// Replaces all topics of the repository
+    pub async fn set_repository_topics(
+        &mut self,
+        owner: &str,
+        name: &str,
+        topics: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let req = self
+            .client
+            .request(
+                Method::PUT,
+                Url::parse(&format!(
+                    "{}/api/v{}/repos/{}/{}/topics",
+                    &self.base_url, API_VERSION, owner, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .json(&ForgejoRepositoryTopicsRequest { topics })
+            .build()?;
+
+        self.do_request_handle_status(req).await?;
+
+        Ok(())
+    }
+
+    /// Moves the repository to another user or organisation
+    pub async fn transfer_repository(
+        &mut self,
+        owner: &str,
+        name: &str,
+        new_owner: &str,
+    ) -> anyhow::Result<ForgejoRepository> {
+        let req = self
+            .client
+            .request(
+                Method::POST,
+                Url::parse(&format!(
+                    "{}/api/v{}/repos/{}/{}/transfer",
+                    &self.base_url, API_VERSION, owner, name
+                ))?,
+            )
+            .headers(self.headers.clone())
+            .json(&ForgejoTransferRepositoryRequest {
+                new_owner: new_owner.to_string(),
+            })
+            .build()?;
+
+        self.do_request_handle_status_parsed::<ForgejoRepository>(req)
+            .await
+    }
+
     pub async fn delete_repository(&mut self, owner: &str, name: &str) -> anyhow::Result<()> {
         let req = self
             .client
@@ -253,6 +579,18 @@ impl ForgejoApi {
         Ok(())
     }
 
+    async fn get_avatar(&mut self, avatar_url: &str) -> anyhow::Result<Bytes> {
+        let req = self
+            .client
+            .request(Method::GET, Url::parse(avatar_url)?)
+            .headers(self.headers.clone())
+            .build()?;
+
+        let res = self.do_request_handle_status(req).await?;
+
+        Ok(res.bytes().await?)
+    }
+
     async fn do_request_handle_status_parsed<T: DeserializeOwned>(
         &mut self,
         req: Request,
@@ -267,7 +605,7 @@ impl ForgejoApi {
 
         let status = res.status();
 
-        return if status.is_success() {
+        if status.is_success() {
             Ok(res)
         } else {
             let response_text = res.text().await?;
@@ -276,19 +614,48 @@ impl ForgejoApi {
                 status,
                 response_text,
             )))
-        };
+        }
     }
 
     async fn do_request(&mut self, req: Request) -> anyhow::Result<Response> {
-        let res = self
-            .service
-            .ready()
-            .await
-            .map_err(|err| anyhow!(err))?
-            .call(req)
+        self.retry_policy
+            .retry("Forgejo", &mut self.service, req, |service, req| {
+                Box::pin(async move {
+                    // only wait for the rate limit while holding the lock, the response is awaited
+                    // afterwards so slow requests like migrations don't block other requests
+                    let response_future = {
+                        let mut service = service.lock().await;
+
+                        service.ready().await.map_err(into_anyhow)?.call(req)
+                    };
+
+                    response_future.await.map_err(into_anyhow)
+                })
+            })
             .await
-            .map_err(|err| anyhow!(err))?;
+    }
+}
+
+impl PageClient for ForgejoApi {
+    async fn get_page(&mut self, url: Url) -> anyhow::Result<Response> {
+        let req = self
+            .client
+            .request(Method::GET, url)
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status(req).await
+    }
+}
 
-        Ok(res)
+/// Keeps the concrete type of the errors of the tower layers, so they can still be inspected
+/// with `downcast_ref` to decide whether to retry
+fn into_anyhow(err: BoxError) -> Error {
+    match err.downcast::<reqwest::Error>() {
+        Ok(err) => Error::from(*err),
+        Err(err) => match err.downcast::<Elapsed>() {
+            Ok(err) => Error::from(*err),
+            Err(err) => Error::from_boxed(err),
+        },
     }
 }
@@ -5,4 +5,19 @@ use thiserror::Error;
 pub enum ForgejoApiError {
     #[error("Forgejo API returned {0} with error message: {1}")]
     NoSuccessStatusCodeError(StatusCode, String),
+    #[error("{0} is not a valid repository url")]
+    InvalidRepositoryUrl(String),
+    #[error("The request can't be retried because its body can't be cloned")]
+    RequestNotRetryable,
+}
+
+impl ForgejoApiError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ForgejoApiError::NoSuccessStatusCodeError(status, _) if *status == StatusCode::NOT_FOUND)
+    }
+
+    /// Whether forgejo rejected the request because the repository already exists
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, ForgejoApiError::NoSuccessStatusCodeError(status, _) if *status == StatusCode::CONFLICT)
+    }
 }
@@ -0,0 +1,183 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::util::time::parse_timestamp;
+
+/// Repositories pushed within this duration are considered active by the automatic interval
+const ACTIVE_REPOSITORY_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const ACTIVE_REPOSITORY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const INACTIVE_REPOSITORY_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often forgejo pulls a mirror, either a fixed duration or derived from the activity of the
+/// source repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MirrorInterval {
+    Fixed(Duration),
+    /// hourly for repositories pushed within the last week, daily for all others
+    Auto,
+}
+
+impl MirrorInterval {
+    /// The interval of the mirror of a repository last pushed at `pushed_at` in the format forgejo
+    /// expects, e.g. `8h0m0s`
+    pub fn resolve(&self, pushed_at: Option<&str>) -> String {
+        let interval = match self {
+            MirrorInterval::Fixed(interval) => *interval,
+            MirrorInterval::Auto => {
+                // repositories without a known push date are treated as inactive
+                let is_active = pushed_at
+                    .and_then(parse_timestamp)
+                    .and_then(|pushed_at| SystemTime::now().duration_since(pushed_at).ok())
+                    .is_some_and(|age| age <= ACTIVE_REPOSITORY_AGE);
+
+                if is_active {
+                    ACTIVE_REPOSITORY_INTERVAL
+                } else {
+                    INACTIVE_REPOSITORY_INTERVAL
+                }
+            }
+        };
+
+        format_interval(interval)
+    }
+}
+
+impl FromStr for MirrorInterval {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(MirrorInterval::Auto);
+        }
+
+        humantime::parse_duration(value)
+            .map(MirrorInterval::Fixed)
+            .map_err(|err| format!("invalid mirror interval {:?}: {}", value, err))
+    }
+}
+
+impl TryFrom<String> for MirrorInterval {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<MirrorInterval> for String {
+    fn from(interval: MirrorInterval) -> Self {
+        interval.to_string()
+    }
+}
+
+impl Display for MirrorInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MirrorInterval::Fixed(interval) => write!(f, "{}", format_interval(*interval)),
+            MirrorInterval::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// Formats the interval like forgejo returns it, so it can be compared to the interval of a mirror
+fn format_interval(interval: Duration) -> String {
+    let seconds = interval.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h{}m{}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m{}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp_ago(age: Duration) -> String {
+        humantime::format_rfc3339(SystemTime::now() - age).to_string()
+    }
+
+    #[test]
+    fn parses_auto_and_durations() {
+        assert_eq!("auto".parse(), Ok(MirrorInterval::Auto));
+        assert_eq!("AUTO".parse(), Ok(MirrorInterval::Auto));
+        assert_eq!(
+            "8h".parse(),
+            Ok(MirrorInterval::Fixed(Duration::from_secs(8 * 60 * 60)))
+        );
+        assert_eq!(
+            "1h 30m".parse(),
+            Ok(MirrorInterval::Fixed(Duration::from_secs(90 * 60)))
+        );
+        assert!("often".parse::<MirrorInterval>().is_err());
+    }
+
+    #[test]
+    fn formats_like_forgejo() {
+        assert_eq!(format_interval(Duration::from_secs(8 * 60 * 60)), "8h0m0s");
+        assert_eq!(format_interval(Duration::from_secs(90 * 60 + 5)), "1h30m5s");
+        assert_eq!(format_interval(Duration::from_secs(30 * 60)), "30m0s");
+        assert_eq!(format_interval(Duration::from_secs(45)), "45s");
+        assert_eq!(format_interval(Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn displays_the_parsable_value() {
+        assert_eq!(MirrorInterval::Auto.to_string(), "auto");
+        assert_eq!(
+            MirrorInterval::Fixed(Duration::from_secs(10 * 60)).to_string(),
+            "10m0s"
+        );
+        assert_eq!(
+            "10m0s".parse(),
+            Ok(MirrorInterval::Fixed(Duration::from_secs(10 * 60)))
+        );
+    }
+
+    #[test]
+    fn serializes_as_string() {
+        let interval = MirrorInterval::Fixed(Duration::from_secs(60 * 60));
+
+        assert_eq!(serde_json::to_string(&interval).unwrap(), "\"1h0m0s\"");
+        assert_eq!(
+            serde_json::from_str::<MirrorInterval>("\"auto\"").unwrap(),
+            MirrorInterval::Auto
+        );
+        assert!(serde_json::from_str::<MirrorInterval>("\"often\"").is_err());
+    }
+
+    #[test]
+    fn fixed_intervals_ignore_the_last_push() {
+        let interval = MirrorInterval::Fixed(Duration::from_secs(8 * 60 * 60));
+
+        assert_eq!(interval.resolve(None), "8h0m0s");
+        assert_eq!(
+            interval.resolve(Some(&timestamp_ago(Duration::from_secs(60)))),
+            "8h0m0s"
+        );
+    }
+
+    #[test]
+    fn auto_interval_is_hourly_for_active_repositories() {
+        let pushed_at = timestamp_ago(Duration::from_secs(24 * 60 * 60));
+
+        assert_eq!(MirrorInterval::Auto.resolve(Some(&pushed_at)), "1h0m0s");
+    }
+
+    #[test]
+    fn auto_interval_is_daily_for_inactive_repositories() {
+        let pushed_at = timestamp_ago(ACTIVE_REPOSITORY_AGE + Duration::from_secs(60 * 60));
+
+        assert_eq!(MirrorInterval::Auto.resolve(Some(&pushed_at)), "24h0m0s");
+        assert_eq!(MirrorInterval::Auto.resolve(None), "24h0m0s");
+        assert_eq!(MirrorInterval::Auto.resolve(Some("never")), "24h0m0s");
+    }
+}
@@ -1,3 +1,5 @@
 pub mod api;
 pub(crate) mod error;
+pub mod interval;
 pub mod models;
+pub mod topics;
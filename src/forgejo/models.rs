@@ -14,9 +14,10 @@ pub enum ForgejoMigrateRepoService {
     Codebase,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, ValueEnum)]
+#[derive(Default, Debug, PartialEq, Eq, Clone, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum ForgejoVisibility {
+    #[default]
     Public,
     Limited,
     Private,
@@ -43,6 +44,35 @@ pub struct ForgejoMigrateRepositoryRequest {
     pub wiki: bool,
 }
 
+/// The changes to a repository, fields which are None are left unchanged
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForgejoEditRepositoryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror_interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForgejoTransferRepositoryRequest {
+    pub new_owner: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForgejoRepositoryTopicsRequest {
+    pub topics: Vec<String>,
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct ForgejoCreateOrganisationRequest {
     pub description: Option<String>,
@@ -55,10 +85,44 @@ pub struct ForgejoCreateOrganisationRequest {
     pub website: Option<String>,
 }
 
-pub type ForgejoGetOrganisationRepositoriesResponse = Vec<ForgejoRepository>;
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForgejoOrganisation {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    pub email: String,
+    pub avatar_url: String,
+    pub description: String,
+    pub website: String,
+    pub location: String,
+    pub visibility: ForgejoVisibility,
+    pub repo_admin_change_team_access: bool,
+}
 
+/// The limits of the api of the instance
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct ForgejoApiSettings {
+    pub max_response_items: usize,
+    pub default_paging_num: usize,
+    pub default_git_trees_per_page: usize,
+    pub default_max_blob_size: i64,
+}
+
+/// The state of a repository which forgejo migrates in a background task
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForgejoMigrationStatus {
+    /// the migration finished, the repository is empty if the migration didn't fetch anything
+    Finished { empty: bool },
+    /// forgejo deleted the repository because the migration failed
+    Failed,
+    /// the migration didn't finish within the timeout
+    TimedOut,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct ForgejoRepository {
     pub id: i64,
     pub owner: ForgejoOwner,
@@ -149,10 +213,11 @@ pub struct ForgejoRepository {
     pub mirror_interval: String,
     #[serde(rename = "mirror_updated")]
     pub mirror_updated: String,
+    pub topics: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ForgejoOwner {
     pub id: i64,
     pub login: String,
@@ -0,0 +1,131 @@
+use log::debug;
+
+/// Forgejo rejects topics which are longer than this
+const MAX_TOPIC_LENGTH: usize = 35;
+/// Forgejo rejects more topics than this per repository
+const MAX_TOPICS: usize = 25;
+
+/// Converts topics to topics forgejo accepts, they are lowercased, every run of characters other than
+/// ascii letters, digits and dots is replaced by a dash and they must start with a letter or a digit,
+/// duplicates are removed and only the first 25 topics are kept
+pub fn normalize_topics(topics: &[String]) -> Vec<String> {
+    let mut normalized = Vec::new();
+
+    for topic in topics {
+        let Some(topic) = normalize_topic(topic) else {
+            debug!("Dropping topic {:?} without any allowed characters", topic);
+            continue;
+        };
+
+        if !normalized.contains(&topic) {
+            normalized.push(topic);
+        }
+    }
+
+    if normalized.len() > MAX_TOPICS {
+        debug!(
+            "Dropping topics {:?}, forgejo only allows {} topics",
+            &normalized[MAX_TOPICS..],
+            MAX_TOPICS
+        );
+        normalized.truncate(MAX_TOPICS);
+    }
+
+    normalized
+}
+
+fn normalize_topic(topic: &str) -> Option<String> {
+    let mut normalized = String::new();
+
+    for char in topic.trim().to_lowercase().chars() {
+        if char.is_ascii_alphanumeric() || char == '.' {
+            normalized.push(char);
+        } else if !normalized.ends_with('-') {
+            normalized.push('-');
+        }
+    }
+
+    // the topic only consists of ascii characters at this point, so it can be cut at any index
+    let normalized = normalized.trim_start_matches(|char: char| !char.is_ascii_alphanumeric());
+    let normalized = &normalized[..normalized.len().min(MAX_TOPIC_LENGTH)];
+    let normalized = normalized.trim_end_matches('-');
+
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics(topics: &[&str]) -> Vec<String> {
+        topics.iter().map(|topic| topic.to_string()).collect()
+    }
+
+    #[test]
+    fn lowercases_and_replaces_disallowed_characters() {
+        assert_eq!(
+            normalize_topics(&topics(&[
+                "Rust",
+                "Command Line",
+                "c++",
+                "node.js",
+                "a__b  c"
+            ])),
+            topics(&["rust", "command-line", "c", "node.js", "a-b-c"])
+        );
+    }
+
+    #[test]
+    fn topics_start_with_a_letter_or_digit() {
+        assert_eq!(
+            normalize_topics(&topics(&["-rust", ".hidden", "  spaced  ", "#1"])),
+            topics(&["rust", "hidden", "spaced", "1"])
+        );
+    }
+
+    #[test]
+    fn drops_topics_without_allowed_characters() {
+        assert_eq!(
+            normalize_topics(&topics(&["", "---", "日本語", "ok"])),
+            topics(&["ok"])
+        );
+    }
+
+    #[test]
+    fn truncates_long_topics() {
+        let normalized = normalize_topics(&topics(&[&"a".repeat(40)]));
+
+        assert_eq!(normalized, topics(&[&"a".repeat(MAX_TOPIC_LENGTH)]));
+    }
+
+    #[test]
+    fn truncated_topics_dont_end_with_a_dash() {
+        let topic = format!("{} b", "a".repeat(MAX_TOPIC_LENGTH - 1));
+
+        assert_eq!(
+            normalize_topics(&[topic]),
+            topics(&[&"a".repeat(MAX_TOPIC_LENGTH - 1)])
+        );
+    }
+
+    #[test]
+    fn removes_duplicates_after_normalizing() {
+        assert_eq!(
+            normalize_topics(&topics(&["Rust", "rust", "RUST ", "cli"])),
+            topics(&["rust", "cli"])
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_topics_up_to_the_limit() {
+        let many = (0..30)
+            .map(|index| format!("topic{}", index))
+            .collect::<Vec<_>>();
+
+        assert_eq!(normalize_topics(&many), many[..MAX_TOPICS].to_vec());
+    }
+}
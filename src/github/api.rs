@@ -1,26 +1,49 @@
 use anyhow::Error;
 use bytes::Bytes;
-use reqwest::{Client, Method, Request, Response};
+use futures_util::stream::LocalBoxStream;
+use log::{debug, warn};
+use regex::Regex;
 use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, Request, Response, Url};
 use serde::de::DeserializeOwned;
-use tower::{Service, ServiceExt};
+use tokio::time::sleep;
 use tower::limit::RateLimit;
+use tower::{Service, ServiceExt};
 
-use crate::github::constants::API_URL;
+use crate::github::constants::{
+    DEFAULT_API_URL, DEFAULT_HOST, ENTERPRISE_API_PATH, MAX_RATE_LIMIT_RETRIES, PAGE_SIZE,
+};
 use crate::github::error::GithubApiError;
 use crate::github::models::{
-    GithubOrganisation, GithubOrganisationRepositoryResponse, GithubRepository, GithubUser,
+    GithubAffiliation, GithubGist, GithubOrganisation, GithubRepository,
+    GithubRepositoryVisibilityFilter, GithubUser,
 };
-use crate::util::http::{CLIENT, USER_AGENT};
+use crate::github::rate_limit::GithubRateLimit;
+use crate::util::http::{paginate, PageClient, CLIENT, USER_AGENT};
+use crate::util::retry::RetryPolicy;
 
 pub struct GithubApi {
+    api_key: String,
     client: Client,
     headers: HeaderMap,
     service: RateLimit<Client>,
+    api_url: String,
+    host: String,
+    /// matches the urls of repositories on the host, capturing `owner/repo`
+    repository_url_regex: Regex,
+    /// the rate limit state of the last response
+    rate_limit: GithubRateLimit,
+    retry_policy: RetryPolicy,
 }
 
 impl GithubApi {
-    pub fn new(api_key: String) -> anyhow::Result<Self> {
+    /// Creates a client for github.com or a github enterprise server, if only one of the api url and
+    /// the host is given then the other one is derived from it
+    pub fn new(
+        api_url: Option<String>,
+        host: Option<String>,
+        api_key: String,
+    ) -> anyhow::Result<Self> {
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", USER_AGENT.parse()?);
         headers.insert("Accept", "application/json".parse()?);
@@ -32,17 +55,71 @@ impl GithubApi {
             .rate_limit(10, std::time::Duration::from_secs(10))
             .service(client.clone());
 
+        let (api_url, host) = match (api_url, host) {
+            (Some(api_url), Some(host)) => (api_url, host),
+            (Some(api_url), None) => {
+                let host = host_of_api_url(&api_url);
+
+                (api_url, host)
+            }
+            (None, Some(host)) if host != DEFAULT_HOST => {
+                (format!("https://{}{}", host, ENTERPRISE_API_PATH), host)
+            }
+            _ => (DEFAULT_API_URL.to_string(), DEFAULT_HOST.to_string()),
+        };
+
+        let repository_url_regex = Regex::new(&format!(
+            r"(?:git@|https?://){}[:/](?<ownerRepoName>.*)",
+            regex::escape(&host)
+        ))?;
+
         Ok(Self {
+            api_key,
             client,
             service,
             headers,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            host,
+            repository_url_regex,
+            rate_limit: GithubRateLimit::default(),
+            retry_policy: RetryPolicy::default(),
         })
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// The host used in the web and clone urls of repositories, e.g. `github.com`
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn repository_url_regex(&self) -> &Regex {
+        &self.repository_url_regex
+    }
+
     pub async fn get_user(&mut self, user: &str) -> anyhow::Result<GithubUser> {
         let req = self
             .client
-            .request(Method::GET, format!("{}/users/{}", API_URL, user))
+            .request(Method::GET, format!("{}/users/{}", self.api_url, user))
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<GithubUser>(req)
+            .await
+    }
+
+    /// Fetches the user the api key belongs to
+    pub async fn get_authenticated_user(&mut self) -> anyhow::Result<GithubUser> {
+        let req = self
+            .client
+            .request(Method::GET, format!("{}/user", self.api_url))
             .headers(self.headers.clone())
             .build()?;
 
@@ -64,51 +141,86 @@ impl GithubApi {
         Ok(res.bytes().await?)
     }
 
-    pub async fn get_repositories_of_user(
+    pub fn get_repositories_of_user(
         &mut self,
         user: &str,
-    ) -> anyhow::Result<Vec<GithubRepository>> {
-        let mut repos = Vec::new();
+    ) -> LocalBoxStream<'_, anyhow::Result<GithubRepository>> {
+        let url = format!(
+            "{}/users/{}/repos?per_page={}",
+            self.api_url, user, PAGE_SIZE
+        );
 
-        let mut last_response_count = 0;
-        let mut index = 1;
-
-        while index == 1 || last_response_count > 0 {
-            let url = format!(
-                "{}/users/{}/repos?page={}&per_page=25",
-                API_URL, user, index
-            );
+        paginate(self, &url)
+    }
 
-            let req = self
-                .client
-                .request(Method::GET, url)
-                .headers(self.headers.clone())
-                .build()?;
+    /// Lists all repositories the api key can access with one of the affiliations, including
+    /// private repositories and repositories of organisations
+    pub fn get_repositories_of_authenticated_user(
+        &mut self,
+        affiliations: &[GithubAffiliation],
+        visibility: &GithubRepositoryVisibilityFilter,
+    ) -> LocalBoxStream<'_, anyhow::Result<GithubRepository>> {
+        let affiliations = affiliations
+            .iter()
+            .map(|affiliation| match affiliation {
+                GithubAffiliation::Owner => "owner",
+                GithubAffiliation::Collaborator => "collaborator",
+                GithubAffiliation::OrganizationMember => "organization_member",
+            })
+            .collect::<Vec<&str>>()
+            .join(",");
+
+        let visibility = match visibility {
+            GithubRepositoryVisibilityFilter::All => "all",
+            GithubRepositoryVisibilityFilter::Public => "public",
+            GithubRepositoryVisibilityFilter::Private => "private",
+        };
 
-            let res = self
-                .do_request_handle_status_parsed::<Vec<GithubRepository>>(req)
-                .await?;
+        let url = format!(
+            "{}/user/repos?per_page={}&affiliation={}&visibility={}",
+            self.api_url, PAGE_SIZE, affiliations, visibility
+        );
 
-            index += 1;
+        paginate(self, &url)
+    }
 
-            if res.len() > 0 {
-                last_response_count = res.len();
+    /// Lists the repositories starred by the user, or by the user the api key belongs to if no user is given
+    pub fn get_starred_repositories(
+        &mut self,
+        user: Option<&str>,
+    ) -> LocalBoxStream<'_, anyhow::Result<GithubRepository>> {
+        let url = match user {
+            Some(user) => format!(
+                "{}/users/{}/starred?per_page={}",
+                self.api_url, user, PAGE_SIZE
+            ),
+            None => format!("{}/user/starred?per_page={}", self.api_url, PAGE_SIZE),
+        };
 
-                repos.extend(res);
-            } else {
-                last_response_count = 0;
-            }
-        }
+        paginate(self, &url)
+    }
 
-        repos.sort_by(|a, b| a.size.cmp(&b.size));
+    /// Lists the gists of the user, or of the user the api key belongs to if no user is given,
+    /// only the gists of the user the api key belongs to include secret gists
+    pub fn get_gists(
+        &mut self,
+        user: Option<&str>,
+    ) -> LocalBoxStream<'_, anyhow::Result<GithubGist>> {
+        let url = match user {
+            Some(user) => format!(
+                "{}/users/{}/gists?per_page={}",
+                self.api_url, user, PAGE_SIZE
+            ),
+            None => format!("{}/gists?per_page={}", self.api_url, PAGE_SIZE),
+        };
 
-        Ok(repos)
+        paginate(self, &url)
     }
 
     pub async fn get_organisation(&mut self, org: &str) -> anyhow::Result<GithubOrganisation> {
         let req = self
             .client
-            .request(Method::GET, format!("{}/orgs/{}", API_URL, org))
+            .request(Method::GET, format!("{}/orgs/{}", self.api_url, org))
             .headers(self.headers.clone())
             .build()?;
 
@@ -130,42 +242,13 @@ impl GithubApi {
         Ok(res.bytes().await?)
     }
 
-    pub async fn get_repositories_of_org(
+    pub fn get_repositories_of_org(
         &mut self,
         org: &str,
-    ) -> anyhow::Result<Vec<GithubRepository>> {
-        let mut repos = Vec::new();
-
-        let mut last_response_count = 0;
-        let mut index = 1;
-
-        while index == 1 || last_response_count > 0 {
-            let url = format!("{}/orgs/{}/repos?page={}&per_page=25", API_URL, org, index);
+    ) -> LocalBoxStream<'_, anyhow::Result<GithubRepository>> {
+        let url = format!("{}/orgs/{}/repos?per_page={}", self.api_url, org, PAGE_SIZE);
 
-            let req = self
-                .client
-                .request(Method::GET, url)
-                .headers(self.headers.clone())
-                .build()?;
-
-            let res = self
-                .do_request_handle_status_parsed::<GithubOrganisationRepositoryResponse>(req)
-                .await?;
-
-            index += 1;
-
-            if res.len() > 0 {
-                last_response_count = res.len();
-
-                repos.extend(res);
-            } else {
-                last_response_count = 0;
-            }
-        }
-
-        repos.sort_by(|a, b| a.size.cmp(&b.size));
-
-        Ok(repos)
+        paginate(self, &url)
     }
 
     pub async fn get_repository(
@@ -175,7 +258,10 @@ impl GithubApi {
     ) -> anyhow::Result<GithubRepository> {
         let req = self
             .client
-            .request(Method::GET, format!("{}/repos/{}/{}", API_URL, owner, repo))
+            .request(
+                Method::GET,
+                format!("{}/repos/{}/{}", self.api_url, owner, repo),
+            )
             .headers(self.headers.clone())
             .build()?;
 
@@ -193,25 +279,156 @@ impl GithubApi {
     }
 
     async fn do_request_handle_status(&mut self, request: Request) -> anyhow::Result<Response> {
-        let res = self.do_request(request).await?;
+        let mut attempt = 0;
+
+        loop {
+            let req = request
+                .try_clone()
+                .ok_or(GithubApiError::RequestNotRetryable)?;
 
-        let status = res.status();
+            let res = self.do_request(req).await?;
+
+            let status = res.status();
+
+            if status.is_success() {
+                return Ok(res);
+            }
 
-        return if status.is_success() {
-            Ok(res)
-        } else {
             let response_text = res.text().await?;
 
-            Err(Error::from(GithubApiError::NoSuccessStatusCodeError(
-                status,
-                response_text,
-            )))
-        };
+            let wait_duration = self
+                .rate_limit
+                .wait_duration(status, &response_text, attempt)
+                .filter(|_| attempt < MAX_RATE_LIMIT_RETRIES);
+
+            match wait_duration {
+                Some(wait_duration) => {
+                    warn!(
+                        "Github rate limit hit for {}, retrying in {}s",
+                        request.url(),
+                        wait_duration.as_secs()
+                    );
+
+                    sleep(wait_duration).await;
+
+                    self.rate_limit = GithubRateLimit::default();
+                    attempt += 1;
+                }
+                None => {
+                    return Err(Error::from(GithubApiError::NoSuccessStatusCodeError(
+                        status,
+                        response_text,
+                    )))
+                }
+            }
+        }
     }
 
     async fn do_request(&mut self, req: Request) -> anyhow::Result<Response> {
-        let res = self.service.ready().await?.call(req).await?;
+        // wait for the budget to reset instead of sending a request which is certain to be rejected
+        if self.rate_limit.is_exhausted() {
+            if let Some(until_reset) = self.rate_limit.until_reset() {
+                warn!(
+                    "Github rate limit exhausted, waiting {}s until it resets",
+                    until_reset.as_secs()
+                );
+
+                sleep(until_reset).await;
+            }
+
+            self.rate_limit = GithubRateLimit::default();
+        }
+
+        let res = self
+            .retry_policy
+            .retry("Github", &mut self.service, req, |service, req| {
+                Box::pin(async move { Ok(service.ready().await?.call(req).await?) })
+            })
+            .await?;
+
+        self.rate_limit = GithubRateLimit::from_headers(res.headers());
+
+        if let (Some(remaining), Some(limit)) = (self.rate_limit.remaining, self.rate_limit.limit) {
+            debug!(
+                "Github rate limit: {}/{} requests remaining, resets in {}s",
+                remaining,
+                limit,
+                self.rate_limit.until_reset().unwrap_or_default().as_secs()
+            );
+        }
 
         Ok(res)
     }
 }
+
+impl PageClient for GithubApi {
+    async fn get_page(&mut self, url: Url) -> anyhow::Result<Response> {
+        let req = self
+            .client
+            .request(Method::GET, url)
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status(req).await
+    }
+}
+
+/// Derives the host used in repository urls from the api url, `https://api.github.com` belongs to
+/// `github.com` while github enterprise server serves the api from the same host, e.g.
+/// `https://ghe.example.com/api/v3`, a port other than the default of the scheme is kept since the
+/// repository urls of such a server contain it as well
+fn host_of_api_url(api_url: &str) -> String {
+    let host = match Url::parse(api_url) {
+        Ok(url) => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => String::new(),
+        },
+        Err(_) => api_url.split('/').next().unwrap_or_default().to_string(),
+    };
+
+    match host.strip_prefix("api.") {
+        Some(host) => host.to_string(),
+        None => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_of_api_url_strips_the_api_subdomain() {
+        assert_eq!(host_of_api_url("https://api.github.com"), "github.com");
+        assert_eq!(
+            host_of_api_url("https://ghe.example.com/api/v3"),
+            "ghe.example.com"
+        );
+    }
+
+    #[test]
+    fn host_of_api_url_keeps_custom_ports() {
+        assert_eq!(
+            host_of_api_url("http://localhost:8080/api/v3"),
+            "localhost:8080"
+        );
+        assert_eq!(
+            host_of_api_url("https://ghe.example.com:8443/api/v3/"),
+            "ghe.example.com:8443"
+        );
+    }
+
+    #[test]
+    fn host_of_api_url_drops_default_ports() {
+        assert_eq!(
+            host_of_api_url("https://ghe.example.com:443/api/v3"),
+            "ghe.example.com"
+        );
+        assert_eq!(host_of_api_url("http://localhost:80"), "localhost");
+    }
+
+    #[test]
+    fn host_of_api_url_accepts_urls_without_scheme() {
+        assert_eq!(host_of_api_url("ghe.example.com/api/v3"), "ghe.example.com");
+    }
+}
@@ -1 +1,20 @@
-pub const API_URL: &str = "https://api.github.com";
+use std::time::Duration;
+
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// the path of the rest api on github enterprise server instances
+pub const ENTERPRISE_API_PATH: &str = "/api/v3";
+
+/// the `type` of repository owners which are organisations
+pub const ORGANISATION_OWNER_TYPE: &str = "Organization";
+
+/// the maximum page size of the list endpoints
+pub const PAGE_SIZE: usize = 100;
+
+/// how often a request is retried after hitting a rate limit before giving up
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+pub const SECONDARY_RATE_LIMIT_MESSAGE: &str = "secondary rate limit";
+pub const SECONDARY_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+pub const SECONDARY_RATE_LIMIT_MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
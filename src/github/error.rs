@@ -5,4 +5,14 @@ use thiserror::Error;
 pub enum GithubApiError {
     #[error("Github API returned {0} with error message: {1}")]
     NoSuccessStatusCodeError(StatusCode, String),
+    #[error("{0} is not a valid github repository url")]
+    InvalidRepositoryUrl(String),
+    #[error("The request can't be retried because its body can't be cloned")]
+    RequestNotRetryable,
+}
+
+impl GithubApiError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, GithubApiError::NoSuccessStatusCodeError(status, _) if *status == StatusCode::NOT_FOUND)
+    }
 }
@@ -1,4 +1,5 @@
 pub mod api;
-mod constants;
+pub(crate) mod constants;
 pub mod error;
 pub mod models;
+mod rate_limit;
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -116,8 +119,6 @@ pub struct GithubPlan {
     pub seats: i64,
 }
 
-pub type GithubOrganisationRepositoryResponse = Vec<GithubRepository>;
-
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GithubRepository {
@@ -381,3 +382,56 @@ pub struct GithubUser {
     #[serde(rename = "updated_at")]
     pub updated_at: String,
 }
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubGist {
+    pub id: String,
+    #[serde(rename = "node_id")]
+    pub node_id: String,
+    pub url: String,
+    #[serde(rename = "html_url")]
+    pub html_url: String,
+    #[serde(rename = "git_pull_url")]
+    pub git_pull_url: String,
+    pub description: Option<String>,
+    pub public: bool,
+    /// the files of the gist keyed by their file name
+    pub files: BTreeMap<String, GithubGistFile>,
+    pub owner: Option<GithubOwner>,
+    #[serde(rename = "created_at")]
+    pub created_at: String,
+    #[serde(rename = "updated_at")]
+    pub updated_at: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubGistFile {
+    pub filename: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub language: Option<String>,
+    #[serde(rename = "raw_url")]
+    pub raw_url: String,
+    pub size: i64,
+}
+
+/// How the authenticated user is related to a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum GithubAffiliation {
+    Owner,
+    Collaborator,
+    #[value(alias = "organization_member")]
+    OrganizationMember,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GithubRepositoryVisibilityFilter {
+    #[default]
+    All,
+    Public,
+    Private,
+}
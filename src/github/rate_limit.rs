@@ -0,0 +1,195 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+use crate::github::constants::{
+    SECONDARY_RATE_LIMIT_BACKOFF, SECONDARY_RATE_LIMIT_MAX_BACKOFF, SECONDARY_RATE_LIMIT_MESSAGE,
+};
+
+/// The rate limit state github reports with every response
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct GithubRateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// the point in time at which the budget is reset
+    pub reset: Option<SystemTime>,
+    pub retry_after: Option<Duration>,
+}
+
+impl GithubRateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+
+        GithubRateLimit {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset").map(|reset| UNIX_EPOCH + Duration::from_secs(reset)),
+            retry_after: header("retry-after").map(Duration::from_secs),
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    /// The duration until the budget is reset, including one second of slack for clock differences
+    pub fn until_reset(&self) -> Option<Duration> {
+        let reset = self.reset?;
+
+        Some(
+            reset
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+                .saturating_add(Duration::from_secs(1)),
+        )
+    }
+
+    /// Returns how long to wait before retrying a failed request, or None if the request didn't
+    /// fail because of a rate limit
+    pub fn wait_duration(&self, status: StatusCode, body: &str, attempt: u32) -> Option<Duration> {
+        if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+
+        if let Some(retry_after) = self.retry_after {
+            return Some(retry_after);
+        }
+
+        if self.is_exhausted() {
+            return self.until_reset();
+        }
+
+        // github asks to wait at least a minute and to back off exponentially for secondary rate limits
+        if body.to_lowercase().contains(SECONDARY_RATE_LIMIT_MESSAGE) {
+            return Some(
+                SECONDARY_RATE_LIMIT_BACKOFF
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(SECONDARY_RATE_LIMIT_MAX_BACKOFF),
+            );
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn parses_the_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("5000"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1706702400"));
+        headers.insert("retry-after", HeaderValue::from_static(" 30 "));
+
+        assert_eq!(
+            GithubRateLimit::from_headers(&headers),
+            GithubRateLimit {
+                limit: Some(5000),
+                remaining: Some(0),
+                reset: Some(UNIX_EPOCH + Duration::from_secs(1_706_702_400)),
+                retry_after: Some(Duration::from_secs(30)),
+            }
+        );
+        assert_eq!(
+            GithubRateLimit::from_headers(&HeaderMap::new()),
+            GithubRateLimit::default()
+        );
+    }
+
+    #[test]
+    fn other_failures_arent_rate_limits() {
+        let rate_limit = GithubRateLimit {
+            remaining: Some(0),
+            retry_after: Some(Duration::from_secs(30)),
+            ..GithubRateLimit::default()
+        };
+
+        assert_eq!(rate_limit.wait_duration(StatusCode::NOT_FOUND, "", 0), None);
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::INTERNAL_SERVER_ERROR, "", 0),
+            None
+        );
+        assert_eq!(
+            GithubRateLimit::default().wait_duration(
+                StatusCode::FORBIDDEN,
+                "Resource not accessible",
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn retry_after_takes_precedence() {
+        let rate_limit = GithubRateLimit {
+            remaining: Some(0),
+            reset: Some(SystemTime::now() + Duration::from_secs(600)),
+            retry_after: Some(Duration::from_secs(30)),
+            ..GithubRateLimit::default()
+        };
+
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::TOO_MANY_REQUESTS, "", 0),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn exhausted_budget_waits_until_the_reset() {
+        let rate_limit = GithubRateLimit {
+            remaining: Some(0),
+            reset: Some(SystemTime::now() + Duration::from_secs(600)),
+            ..GithubRateLimit::default()
+        };
+
+        let wait = rate_limit
+            .wait_duration(StatusCode::FORBIDDEN, "API rate limit exceeded", 0)
+            .unwrap();
+
+        assert!(wait > Duration::from_secs(595) && wait <= Duration::from_secs(601));
+    }
+
+    #[test]
+    fn past_resets_only_wait_for_the_slack() {
+        let rate_limit = GithubRateLimit {
+            remaining: Some(0),
+            reset: Some(SystemTime::now() - Duration::from_secs(60)),
+            ..GithubRateLimit::default()
+        };
+
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::FORBIDDEN, "", 0),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn secondary_rate_limits_back_off_exponentially() {
+        let rate_limit = GithubRateLimit::default();
+        let body = "You have exceeded a Secondary Rate Limit. Please wait a few minutes.";
+
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::FORBIDDEN, body, 0),
+            Some(SECONDARY_RATE_LIMIT_BACKOFF)
+        );
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::FORBIDDEN, body, 2),
+            Some(SECONDARY_RATE_LIMIT_BACKOFF * 4)
+        );
+        assert_eq!(
+            rate_limit.wait_duration(StatusCode::FORBIDDEN, body, 10),
+            Some(SECONDARY_RATE_LIMIT_MAX_BACKOFF)
+        );
+    }
+}
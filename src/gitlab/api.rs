@@ -0,0 +1,376 @@
+use anyhow::Error;
+use bytes::Bytes;
+use futures_util::stream::{self, LocalBoxStream};
+use futures_util::{StreamExt, TryStreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, Request, Response, Url};
+use serde::de::DeserializeOwned;
+use tower::limit::RateLimit;
+use tower::{Service, ServiceExt};
+
+use crate::gitlab::constants::{API_PATH, PAGE_SIZE};
+use crate::gitlab::error::GitlabApiError;
+use crate::gitlab::models::{GitlabGroup, GitlabProject, GitlabUser, GitlabUsersResponse};
+use crate::util::http::{paginate, PageClient, CLIENT, USER_AGENT};
+use crate::util::retry::RetryPolicy;
+
+pub struct GitlabApi {
+    api_key: String,
+    client: Client,
+    headers: HeaderMap,
+    service: RateLimit<Client>,
+    base_url: String,
+    retry_policy: RetryPolicy,
+}
+
+impl GitlabApi {
+    pub fn new(base_url: String, api_key: String) -> anyhow::Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", USER_AGENT.parse()?);
+        headers.insert("Accept", "application/json".parse()?);
+        headers.insert("Authorization", format!("Bearer {}", api_key).parse()?);
+
+        let client = CLIENT.clone();
+
+        let service = tower::ServiceBuilder::new()
+            .rate_limit(10, std::time::Duration::from_secs(10))
+            .service(client.clone());
+
+        Ok(Self {
+            api_key,
+            client,
+            headers,
+            service,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            retry_policy: RetryPolicy::default(),
+        })
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    pub async fn get_user(&mut self, user: &str) -> anyhow::Result<GitlabUser> {
+        let req = self
+            .client
+            .request(Method::GET, self.api_url("/users"))
+            .query(&[("username", user)])
+            .headers(self.headers.clone())
+            .build()?;
+
+        let users = self
+            .do_request_handle_status_parsed::<GitlabUsersResponse>(req)
+            .await?;
+
+        let user_id = match users.first() {
+            Some(found) => found.id,
+            None => return Err(Error::from(GitlabApiError::UserNotFound(user.to_string()))),
+        };
+
+        let req = self
+            .client
+            .request(Method::GET, self.api_url(&format!("/users/{}", user_id)))
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<GitlabUser>(req)
+            .await
+    }
+
+    pub async fn get_user_avatar(&mut self, user: &str) -> anyhow::Result<Option<Bytes>> {
+        let user = self.get_user(user).await?;
+
+        self.get_avatar(user.avatar_url).await
+    }
+
+    pub fn get_projects_of_user<'a>(
+        &'a mut self,
+        user: &'a str,
+    ) -> LocalBoxStream<'a, anyhow::Result<GitlabProject>> {
+        // the projects of a user can only be listed by the id of the user
+        stream::once(async move {
+            let user = self.get_user(user).await?;
+
+            anyhow::Ok(self.get_paginated_projects(&format!("/users/{}/projects", user.id), &[]))
+        })
+        .try_flatten()
+        .boxed_local()
+    }
+
+    pub async fn get_group(&mut self, group: &str) -> anyhow::Result<GitlabGroup> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                self.api_url(&format!("/groups/{}", encode_path(group))),
+            )
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<GitlabGroup>(req)
+            .await
+    }
+
+    pub async fn get_group_avatar(&mut self, group: &str) -> anyhow::Result<Option<Bytes>> {
+        let group = self.get_group(group).await?;
+
+        self.get_avatar(group.avatar_url).await
+    }
+
+    /// Lists all projects of a group, including the projects of all of its subgroups
+    pub fn get_projects_of_group(
+        &mut self,
+        group: &str,
+    ) -> LocalBoxStream<'_, anyhow::Result<GitlabProject>> {
+        self.get_paginated_projects(
+            &format!("/groups/{}/projects", encode_path(group)),
+            &[("include_subgroups", "true")],
+        )
+    }
+
+    pub async fn get_project(&mut self, project: &str) -> anyhow::Result<GitlabProject> {
+        let req = self
+            .client
+            .request(
+                Method::GET,
+                self.api_url(&format!("/projects/{}", encode_path(project))),
+            )
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status_parsed::<GitlabProject>(req)
+            .await
+    }
+
+    fn get_paginated_projects(
+        &mut self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> LocalBoxStream<'_, anyhow::Result<GitlabProject>> {
+        let query = query
+            .iter()
+            .map(|(key, value)| format!("&{}={}", key, value))
+            .collect::<String>();
+
+        let url = format!("{}?per_page={}{}", self.api_url(path), PAGE_SIZE, query);
+
+        paginate(self, &url)
+    }
+
+    async fn get_avatar(&mut self, avatar_url: Option<String>) -> anyhow::Result<Option<Bytes>> {
+        let avatar_url = match avatar_url {
+            Some(url) if !url.is_empty() => url,
+            _ => return Ok(None),
+        };
+
+        // gitlab returns relative avatar urls for uploads on some instances
+        let avatar_url = if avatar_url.starts_with('/') {
+            format!("{}{}", self.base_url, avatar_url)
+        } else {
+            avatar_url
+        };
+
+        let req = self
+            .client
+            .request(Method::GET, avatar_url)
+            .headers(self.headers.clone())
+            .build()?;
+
+        let res = self.do_request_handle_status(req).await?;
+
+        Ok(Some(res.bytes().await?))
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}{}{}", self.base_url, API_PATH, path)
+    }
+
+    async fn do_request_handle_status_parsed<T: DeserializeOwned>(
+        &mut self,
+        req: Request,
+    ) -> anyhow::Result<T> {
+        let res = self.do_request_handle_status(req).await?;
+
+        Ok(res.json().await?)
+    }
+
+    async fn do_request_handle_status(&mut self, request: Request) -> anyhow::Result<Response> {
+        let res = self.do_request(request).await?;
+
+        let status = res.status();
+
+        if status.is_success() {
+            Ok(res)
+        } else {
+            let response_text = res.text().await?;
+
+            Err(Error::from(GitlabApiError::NoSuccessStatusCodeError(
+                status,
+                response_text,
+            )))
+        }
+    }
+
+    async fn do_request(&mut self, req: Request) -> anyhow::Result<Response> {
+        self.retry_policy
+            .retry("Gitlab", &mut self.service, req, |service, req| {
+                Box::pin(async move { Ok(service.ready().await?.call(req).await?) })
+            })
+            .await
+    }
+}
+
+impl PageClient for GitlabApi {
+    async fn get_page(&mut self, url: Url) -> anyhow::Result<Response> {
+        let req = self
+            .client
+            .request(Method::GET, url)
+            .headers(self.headers.clone())
+            .build()?;
+
+        self.do_request_handle_status(req).await
+    }
+}
+
+/// Gitlab addresses groups and projects by their url-encoded full path, e.g. `group%2Fsubgroup`
+fn encode_path(path: &str) -> String {
+    path.trim_matches('/').replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    type Page = (Option<String>, serde_json::Value);
+
+    /// Serves the pages built from the base url in order over plain http and records the
+    /// requested paths
+    async fn serve(pages: impl FnOnce(&str) -> Vec<Page>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let responses = pages(&base_url);
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requested.clone();
+
+        tokio::spawn(async move {
+            for (link, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+
+                let request = String::from_utf8(request).unwrap();
+                let path = request.split(' ').nth(1).unwrap().to_string();
+                recorded.lock().unwrap().push(path);
+
+                let body = body.to_string();
+                let link = link
+                    .map(|link| format!("Link: {}\r\n", link))
+                    .unwrap_or_default();
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                    body.len(),
+                    link,
+                    body
+                );
+
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (base_url, requested)
+    }
+
+    fn project(id: i64, path_with_namespace: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": path_with_namespace,
+            "path": path_with_namespace.rsplit('/').next().unwrap(),
+            "path_with_namespace": path_with_namespace,
+            "name_with_namespace": path_with_namespace,
+            "description": null,
+            "default_branch": "main",
+            "visibility": "public",
+            "http_url_to_repo": format!("https://gitlab.com/{}.git", path_with_namespace),
+            "ssh_url_to_repo": format!("git@gitlab.com:{}.git", path_with_namespace),
+            "web_url": format!("https://gitlab.com/{}", path_with_namespace),
+            "avatar_url": null,
+            "star_count": 0,
+            "last_activity_at": "2024-01-01T00:00:00Z",
+            "namespace": {
+                "id": 1,
+                "name": "group",
+                "path": "group",
+                "kind": "group",
+                "full_path": "group",
+                "parent_id": null,
+                "avatar_url": null,
+                "web_url": "https://gitlab.com/groups/group"
+            },
+            "forked_from_project": null
+        })
+    }
+
+    #[tokio::test]
+    async fn lists_all_pages_of_the_projects_of_a_group() {
+        let (base_url, requested) = serve(|base_url| {
+            vec![
+                (
+                    Some(format!(
+                        r#"<{}/api/v4/groups/group%2Fsub/projects?page=2>; rel="next""#,
+                        base_url
+                    )),
+                    json!([project(1, "group/sub/a"), project(2, "group/sub/b")]),
+                ),
+                (
+                    Some(format!(
+                        r#"<{}/api/v4/groups/group%2Fsub/projects?page=1>; rel="first""#,
+                        base_url
+                    )),
+                    json!([project(3, "group/sub/c")]),
+                ),
+            ]
+        })
+        .await;
+
+        let mut api = GitlabApi::new(base_url, String::from("token")).unwrap();
+
+        let projects = api
+            .get_projects_of_group("group/sub")
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            projects
+                .iter()
+                .map(|project| project.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec![
+                "/api/v4/groups/group%2Fsub/projects?per_page=100&include_subgroups=true&page=1",
+                "/api/v4/groups/group%2Fsub/projects?page=2",
+            ]
+        );
+    }
+}
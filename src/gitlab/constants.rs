@@ -0,0 +1,5 @@
+pub const DEFAULT_URL: &str = "https://gitlab.com";
+pub const API_PATH: &str = "/api/v4";
+
+/// the maximum page size of the list endpoints
+pub const PAGE_SIZE: usize = 100;
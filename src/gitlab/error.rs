@@ -0,0 +1,10 @@
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GitlabApiError {
+    #[error("Gitlab API returned {0} with error message: {1}")]
+    NoSuccessStatusCodeError(StatusCode, String),
+    #[error("Gitlab user {0} could not be found")]
+    UserNotFound(String),
+}
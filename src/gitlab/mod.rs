@@ -0,0 +1,4 @@
+pub mod api;
+pub(crate) mod constants;
+pub mod error;
+pub mod models;
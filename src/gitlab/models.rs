@@ -0,0 +1,105 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabGroup {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "full_name")]
+    pub full_name: String,
+    #[serde(rename = "full_path")]
+    pub full_path: String,
+    pub description: Option<String>,
+    pub visibility: String,
+    #[serde(rename = "avatar_url")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "web_url")]
+    pub web_url: String,
+    #[serde(rename = "parent_id")]
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabProject {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "path_with_namespace")]
+    pub path_with_namespace: String,
+    #[serde(rename = "name_with_namespace")]
+    pub name_with_namespace: String,
+    pub description: Option<String>,
+    #[serde(rename = "default_branch")]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub visibility: String,
+    #[serde(rename = "http_url_to_repo")]
+    pub http_url_to_repo: String,
+    #[serde(rename = "ssh_url_to_repo")]
+    pub ssh_url_to_repo: String,
+    #[serde(rename = "web_url")]
+    pub web_url: String,
+    #[serde(rename = "avatar_url")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "star_count")]
+    pub star_count: i64,
+    #[serde(rename = "forks_count", default)]
+    pub forks_count: i64,
+    #[serde(rename = "last_activity_at")]
+    pub last_activity_at: String,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(rename = "empty_repo", default)]
+    pub empty_repo: bool,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    pub namespace: GitlabNamespace,
+    #[serde(rename = "forked_from_project")]
+    pub forked_from_project: Option<GitlabForkedFromProject>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabNamespace {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub kind: String,
+    #[serde(rename = "full_path")]
+    pub full_path: String,
+    #[serde(rename = "parent_id")]
+    pub parent_id: Option<i64>,
+    #[serde(rename = "avatar_url")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "web_url")]
+    pub web_url: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabForkedFromProject {
+    pub id: i64,
+    #[serde(rename = "path_with_namespace")]
+    pub path_with_namespace: String,
+}
+
+pub type GitlabUsersResponse = Vec<GitlabUser>;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabUser {
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+    pub state: String,
+    #[serde(rename = "avatar_url")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "web_url")]
+    pub web_url: String,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    #[serde(rename = "website_url")]
+    pub website_url: Option<String>,
+}
@@ -1,8 +1,15 @@
 use clap::Parser;
+use env_logger::{Target, WriteStyle};
 
 use cli::commands;
-use commands::{delete_forgejo_organisation, mirror_organisation, mirror_repository, mirror_user};
+use commands::{
+    apply_manifest, delete_forgejo_organisation, mirror_gists, mirror_gitea_organisation,
+    mirror_gitea_repository, mirror_gitea_user, mirror_gitlab_group, mirror_gitlab_repository,
+    mirror_gitlab_user, mirror_me, mirror_organisation, mirror_repository, mirror_starred,
+    mirror_user, prune_mirrors, sync_mirrors, update_mirror_settings,
+};
 
+use crate::cli::progress::ProgressLogWriter;
 use crate::cli::{Cli, Commands};
 use crate::config::apply_config;
 
@@ -10,6 +17,10 @@ mod cli;
 mod config;
 mod forgejo;
 mod github;
+mod gitlab;
+mod manifest;
+mod source;
+mod state;
 mod util;
 
 pub mod built_info {
@@ -21,6 +32,12 @@ pub mod built_info {
 async fn main() -> anyhow::Result<()> {
     env_logger::builder()
         .filter_level(log::LevelFilter::Info)
+        .write_style(if console::colors_enabled_stderr() {
+            WriteStyle::Always
+        } else {
+            WriteStyle::Never
+        })
+        .target(Target::Pipe(Box::new(ProgressLogWriter)))
         .parse_default_env()
         .init();
 
@@ -32,6 +49,24 @@ async fn main() -> anyhow::Result<()> {
         Commands::MirrorOrg(cmd) => mirror_organisation(cmd).await,
         Commands::MirrorUser(cmd) => mirror_user(cmd).await,
         Commands::MirrorRepo(cmd) => mirror_repository(cmd).await,
+        Commands::MirrorMe(cmd) => mirror_me(cmd).await,
+        Commands::MirrorStarred(cmd) => mirror_starred(cmd).await,
+        Commands::MirrorGists(cmd) => mirror_gists(cmd).await,
+        Commands::MirrorGitlabGroup(cmd) => mirror_gitlab_group(cmd).await,
+        Commands::MirrorGitlabUser(cmd) => mirror_gitlab_user(cmd).await,
+        Commands::MirrorGitlabRepo(cmd) => mirror_gitlab_repository(cmd).await,
+        Commands::MirrorGiteaOrg(cmd) => mirror_gitea_organisation(cmd).await,
+        Commands::MirrorGiteaUser(cmd) => mirror_gitea_user(cmd).await,
+        Commands::MirrorGiteaRepo(cmd) => mirror_gitea_repository(cmd).await,
+        Commands::Apply(cmd) => apply_manifest(cmd).await,
+        Commands::Plan(mut cmd) => {
+            cmd.run.dry_run = true;
+
+            apply_manifest(cmd).await
+        }
+        Commands::Sync(cmd) => sync_mirrors(cmd).await,
+        Commands::UpdateMirrorSettings(cmd) => update_mirror_settings(cmd).await,
+        Commands::Prune(cmd) => prune_mirrors(cmd).await,
         Commands::DeleteOrg(cmd) => delete_forgejo_organisation(cmd).await,
     };
 }
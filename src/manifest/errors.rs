@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("The manifest entry for the repository {0} is missing the targetOwner")]
+    MissingTargetOwner(String),
+    #[error("The manifest entry {0} doesn't specify the {1} and it isn't set in the CLI or the config file either")]
+    MissingSourceArgument(String, String),
+    #[error(
+        "The manifest entry {0} specifies both private and visibility, only one of them may be set"
+    )]
+    ConflictingVisibility(String),
+}
@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::manifest::errors::ManifestError;
+use crate::manifest::models::MirrorManifest;
+
+pub(crate) mod errors;
+pub mod models;
+
+pub async fn load_manifest(path: &Path) -> anyhow::Result<MirrorManifest> {
+    let manifest = serde_json::from_str::<MirrorManifest>(&fs::read_to_string(path).await?)?;
+
+    // private only applies to the repositories while visibility applies to the owner and its
+    // repositories, setting both is ambiguous
+    if let Some(entry) = manifest
+        .mirrors
+        .iter()
+        .find(|entry| entry.private.is_some() && entry.visibility.is_some())
+    {
+        return Err(ManifestError::ConflictingVisibility(entry.name.clone()).into());
+    }
+
+    Ok(manifest)
+}
@@ -0,0 +1,56 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::forgejo::interval::MirrorInterval;
+use crate::forgejo::models::ForgejoVisibility;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorManifest {
+    pub mirrors: Vec<MirrorManifestEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorManifestSource {
+    Github,
+    Gitlab,
+    Gitea,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorManifestEntryKind {
+    Organisation,
+    User,
+    Repository,
+}
+
+/// A single organisation, user or repository to mirror, every unset option falls back to the
+/// value given in the CLI or the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorManifestEntry {
+    pub source: MirrorManifestSource,
+    #[serde(rename = "type")]
+    pub kind: MirrorManifestEntryKind,
+    /// the name of the organisation or user, or the url of the repository to mirror
+    pub name: String,
+    /// the url of the gitlab or gitea instance, or the api url of a github enterprise server
+    pub source_url: Option<String>,
+    pub source_token: Option<String>,
+    pub target_owner: Option<String>,
+    pub target_display_name: Option<String>,
+    pub target_repository_name: Option<String>,
+    /// the visibility of the forgejo organisation, the repositories are private if it's private
+    pub visibility: Option<ForgejoVisibility>,
+    /// whether the repositories are private, can't be combined with the visibility
+    pub private: Option<bool>,
+    pub migrate_lfs: Option<bool>,
+    pub migrate_wiki: Option<bool>,
+    pub migrate_labels: Option<bool>,
+    pub migrate_issues: Option<bool>,
+    pub migrate_pull_requests: Option<bool>,
+    pub migrate_releases: Option<bool>,
+    pub migrate_milestones: Option<bool>,
+    pub mirror_interval: Option<MirrorInterval>,
+}
@@ -0,0 +1,136 @@
+use bytes::Bytes;
+use clap::ValueEnum;
+use futures_util::stream::LocalBoxStream;
+use futures_util::{StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::forgejo::api::ForgejoApi;
+use crate::forgejo::error::ForgejoApiError;
+use crate::forgejo::models::{ForgejoMigrateRepoService, ForgejoRepository, ForgejoVisibility};
+use crate::source::models::{
+    SourceOwner, SourceOwnerKind, SourceRepository, SourceRepositoryVisibility,
+};
+use crate::source::SourceProvider;
+
+/// Another gitea or forgejo instance used as source
+impl SourceProvider for ForgejoApi {
+    fn service(&self) -> ForgejoMigrateRepoService {
+        ForgejoMigrateRepoService::Gitea
+    }
+
+    fn auth_token(&self) -> String {
+        self.api_key().to_string()
+    }
+
+    async fn get_owner(
+        &mut self,
+        kind: SourceOwnerKind,
+        name: &str,
+    ) -> anyhow::Result<SourceOwner> {
+        let base_url = self.base_url().trim_end_matches('/').to_string();
+
+        match kind {
+            SourceOwnerKind::Organisation => {
+                let org = self.get_organisation(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: org.name.clone(),
+                    html_url: format!("{}/{}", base_url, org.name),
+                    name: org.name,
+                    display_name: Some(org.full_name).filter(|name| !name.is_empty()),
+                    description: Some(org.description).filter(|desc| !desc.is_empty()),
+                    website: Some(org.website).filter(|website| !website.is_empty()),
+                    location: Some(org.location).filter(|location| !location.is_empty()),
+                    visibility: Some(org.visibility),
+                })
+            }
+            SourceOwnerKind::User => {
+                let user = self.get_user(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: user.login.clone(),
+                    html_url: format!("{}/{}", base_url, user.login),
+                    name: user.login,
+                    display_name: Some(user.full_name).filter(|name| !name.is_empty()),
+                    description: Some(user.description).filter(|desc| !desc.is_empty()),
+                    website: Some(user.website).filter(|website| !website.is_empty()),
+                    location: Some(user.location).filter(|location| !location.is_empty()),
+                    visibility: ForgejoVisibility::from_str(&user.visibility, true).ok(),
+                })
+            }
+        }
+    }
+
+    async fn get_owner_avatar(&mut self, owner: &SourceOwner) -> anyhow::Result<Option<Bytes>> {
+        let avatar = match owner.kind {
+            SourceOwnerKind::Organisation => self.get_organisation_avatar(&owner.login).await?,
+            SourceOwnerKind::User => self.get_user_avatar(&owner.login).await?,
+        };
+
+        Ok(Some(avatar))
+    }
+
+    fn get_repositories<'a>(
+        &'a mut self,
+        owner: &'a SourceOwner,
+    ) -> LocalBoxStream<'a, anyhow::Result<SourceRepository>> {
+        let repos = match owner.kind {
+            SourceOwnerKind::Organisation => self.get_organisation_repositories(&owner.login),
+            SourceOwnerKind::User => self.get_user_repositories(&owner.login),
+        };
+
+        repos.map_ok(SourceRepository::from).boxed_local()
+    }
+
+    async fn get_repository(&mut self, url: &str) -> anyhow::Result<SourceRepository> {
+        lazy_static! {
+            static ref OWNER_REPO_NAME_REGEX: Regex =
+                Regex::new(r"(?<owner>[^/:]+)/(?<repoName>[^/]+?)(?:\.git)?/?$").unwrap();
+        }
+
+        let captures = OWNER_REPO_NAME_REGEX
+            .captures(url)
+            .ok_or(ForgejoApiError::InvalidRepositoryUrl(url.to_string()))?;
+
+        let repo =
+            ForgejoApi::get_repository(self, &captures["owner"], &captures["repoName"]).await?;
+
+        Ok(SourceRepository::from(repo))
+    }
+}
+
+impl From<ForgejoRepository> for SourceRepository {
+    fn from(repo: ForgejoRepository) -> Self {
+        SourceRepository {
+            id: repo.id.to_string(),
+            name: repo.name,
+            full_name: repo.full_name,
+            owner: repo.owner.login,
+            description: Some(repo.description).filter(|desc| !desc.is_empty()),
+            clone_url: repo.clone_url,
+            html_url: repo.html_url,
+            homepage: Some(repo.website).filter(|website| !website.is_empty()),
+            default_branch: Some(repo.default_branch).filter(|branch| !branch.is_empty()),
+            private: repo.private,
+            fork: repo.fork,
+            archived: repo.archived,
+            size: repo.size,
+            empty: Some(repo.empty),
+            language: Some(repo.language).filter(|language| !language.is_empty()),
+            topics: repo.topics.unwrap_or_default(),
+            pushed_at: Some(repo.updated_at).filter(|updated_at| !updated_at.is_empty()),
+            template: repo.template,
+            disabled: false,
+            visibility: if repo.private {
+                SourceRepositoryVisibility::Private
+            } else if repo.internal {
+                SourceRepositoryVisibility::Internal
+            } else {
+                SourceRepositoryVisibility::Public
+            },
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use futures_util::stream::LocalBoxStream;
+use futures_util::{StreamExt, TryStreamExt};
+
+use crate::forgejo::models::ForgejoMigrateRepoService;
+use crate::github::api::GithubApi;
+use crate::github::error::GithubApiError;
+use crate::github::models::{GithubGist, GithubRepository};
+use crate::source::models::{
+    SourceOwner, SourceOwnerKind, SourceRepository, SourceRepositoryVisibility,
+};
+use crate::source::SourceProvider;
+
+/// descriptions of gists can be long, so names derived from them are cut off
+const MAX_GIST_REPOSITORY_NAME_LENGTH: usize = 64;
+
+impl SourceProvider for GithubApi {
+    fn service(&self) -> ForgejoMigrateRepoService {
+        ForgejoMigrateRepoService::Github
+    }
+
+    fn auth_token(&self) -> String {
+        self.api_key().to_string()
+    }
+
+    async fn get_owner(
+        &mut self,
+        kind: SourceOwnerKind,
+        name: &str,
+    ) -> anyhow::Result<SourceOwner> {
+        match kind {
+            SourceOwnerKind::Organisation => {
+                let org = self.get_organisation(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: org.login.clone(),
+                    name: org.login,
+                    display_name: org.name,
+                    description: org.description,
+                    website: org.blog,
+                    location: org.location,
+                    html_url: org.html_url,
+                    visibility: None,
+                })
+            }
+            SourceOwnerKind::User => {
+                let user = self.get_user(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: user.login.clone(),
+                    name: user.login,
+                    display_name: user.name,
+                    description: None,
+                    website: user.blog,
+                    location: user.location,
+                    html_url: user.html_url,
+                    visibility: None,
+                })
+            }
+        }
+    }
+
+    async fn get_owner_avatar(&mut self, owner: &SourceOwner) -> anyhow::Result<Option<Bytes>> {
+        let avatar = match owner.kind {
+            SourceOwnerKind::Organisation => self.get_organisation_avatar(&owner.login).await?,
+            SourceOwnerKind::User => self.get_user_avatar(&owner.login).await?,
+        };
+
+        Ok(Some(avatar))
+    }
+
+    fn get_repositories<'a>(
+        &'a mut self,
+        owner: &'a SourceOwner,
+    ) -> LocalBoxStream<'a, anyhow::Result<SourceRepository>> {
+        let repos = match owner.kind {
+            SourceOwnerKind::Organisation => self.get_repositories_of_org(&owner.login),
+            SourceOwnerKind::User => self.get_repositories_of_user(&owner.login),
+        };
+
+        repos.map_ok(SourceRepository::from).boxed_local()
+    }
+
+    async fn get_repository(&mut self, url: &str) -> anyhow::Result<SourceRepository> {
+        let owner_repo_name = match self.repository_url_regex().captures(url) {
+            Some(captures) => captures["ownerRepoName"].to_string(),
+            None => url.to_string(),
+        };
+
+        let owner_repo_name_parts = owner_repo_name
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .split('/')
+            .collect::<Vec<&str>>();
+
+        if owner_repo_name_parts.len() != 2 {
+            return Err(GithubApiError::InvalidRepositoryUrl(url.to_string()).into());
+        }
+
+        let repo =
+            GithubApi::get_repository(self, owner_repo_name_parts[0], owner_repo_name_parts[1])
+                .await?;
+
+        Ok(SourceRepository::from(repo))
+    }
+}
+
+impl From<GithubRepository> for SourceRepository {
+    fn from(repo: GithubRepository) -> Self {
+        SourceRepository {
+            id: repo.id.to_string(),
+            name: repo.name,
+            full_name: repo.full_name,
+            owner: repo.owner.login,
+            description: repo.description,
+            clone_url: repo.clone_url,
+            html_url: repo.html_url,
+            homepage: repo.homepage,
+            default_branch: Some(repo.default_branch),
+            private: repo.private,
+            fork: repo.fork,
+            archived: repo.archived,
+            size: repo.size,
+            // github reports a size of 0 for small repositories as well, only a positive size is conclusive
+            empty: (repo.size > 0).then_some(false),
+            language: repo.language,
+            topics: repo.topics,
+            pushed_at: Some(repo.pushed_at),
+            template: repo.is_template,
+            disabled: repo.disabled,
+            visibility: SourceRepositoryVisibility::from(repo.visibility.as_str()),
+        }
+    }
+}
+
+impl From<GithubGist> for SourceRepository {
+    fn from(gist: GithubGist) -> Self {
+        let name = gist_repository_name(&gist);
+        let owner = gist.owner.map(|owner| owner.login).unwrap_or_default();
+        let language = gist.files.values().find_map(|file| file.language.clone());
+
+        SourceRepository {
+            full_name: format!("{}/{}", &owner, &gist.id),
+            id: gist.id,
+            name,
+            owner,
+            description: gist
+                .description
+                .filter(|description| !description.is_empty()),
+            clone_url: gist.git_pull_url,
+            html_url: gist.html_url,
+            private: !gist.public,
+            language,
+            pushed_at: Some(gist.updated_at),
+            visibility: if gist.public {
+                SourceRepositoryVisibility::Public
+            } else {
+                SourceRepositoryVisibility::Private
+            },
+            ..SourceRepository::default()
+        }
+    }
+}
+
+/// Descriptions and file names of gists aren't unique, so the id is appended to the name of every gist
+/// whose name is shared with another gist, which keeps the names independent of the listing order
+pub fn disambiguate_gist_names(repos: &mut [SourceRepository]) {
+    let mut counts = HashMap::new();
+
+    for repo in repos.iter() {
+        *counts.entry(repo.name.to_lowercase()).or_insert(0) += 1;
+    }
+
+    for repo in repos.iter_mut() {
+        if counts[&repo.name.to_lowercase()] > 1 {
+            repo.name = format!("{}-{}", &repo.name, &repo.id);
+        }
+    }
+}
+
+/// Derives a valid forgejo repository name from the description of the gist, or from its first
+/// file if it has no description, falling back to the id of the gist
+fn gist_repository_name(gist: &GithubGist) -> String {
+    let source = gist
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty())
+        .or(gist.files.keys().next().map(String::as_str))
+        .unwrap_or(&gist.id);
+
+    let mut name = String::new();
+
+    for char in source.chars() {
+        if char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.') {
+            name.push(char);
+        } else if !name.ends_with('-') {
+            name.push('-');
+        }
+
+        if name.len() >= MAX_GIST_REPOSITORY_NAME_LENGTH {
+            break;
+        }
+    }
+
+    let mut name = name.trim_matches(['-', '.']).to_string();
+
+    // forgejo reserves these suffixes for its own routes
+    if [".git", ".wiki", ".rss", ".atom"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        if let Some(index) = name.rfind('.') {
+            name.replace_range(index..index + 1, "-");
+        }
+    }
+
+    if name.is_empty() {
+        gist.id.clone()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::github::models::GithubGistFile;
+
+    use super::*;
+
+    fn gist(id: &str, description: Option<&str>, files: &[&str]) -> GithubGist {
+        GithubGist {
+            id: id.to_string(),
+            description: description.map(str::to_string),
+            files: files
+                .iter()
+                .map(|file| (file.to_string(), GithubGistFile::default()))
+                .collect(),
+            ..GithubGist::default()
+        }
+    }
+
+    #[test]
+    fn gist_names_come_from_the_description() {
+        assert_eq!(
+            gist_repository_name(&gist("abc", Some("Backup script for my NAS!"), &["a.sh"])),
+            "Backup-script-for-my-NAS"
+        );
+    }
+
+    #[test]
+    fn gist_names_fall_back_to_the_first_file_and_the_id() {
+        assert_eq!(
+            gist_repository_name(&gist("abc", Some("  "), &["notes.md", "z.txt"])),
+            "notes.md"
+        );
+        assert_eq!(gist_repository_name(&gist("abc", None, &[])), "abc");
+        assert_eq!(gist_repository_name(&gist("abc", Some("!!!"), &[])), "abc");
+    }
+
+    #[test]
+    fn gist_names_avoid_reserved_suffixes() {
+        assert_eq!(
+            gist_repository_name(&gist("abc", None, &["notes.wiki"])),
+            "notes-wiki"
+        );
+        assert_eq!(
+            gist_repository_name(&gist("abc", Some("mirror.git"), &[])),
+            "mirror-git"
+        );
+    }
+
+    #[test]
+    fn gist_names_are_cut_off() {
+        let description = "word ".repeat(30);
+        let name = gist_repository_name(&gist("abc", Some(&description), &[]));
+
+        assert!(name.len() <= MAX_GIST_REPOSITORY_NAME_LENGTH);
+        assert!(!name.ends_with('-'));
+    }
+
+    #[test]
+    fn ambiguous_gist_names_get_the_id() {
+        let mut repos = vec![
+            SourceRepository::from(gist("aaa", Some("Backup script"), &[])),
+            SourceRepository::from(gist("bbb", Some("notes"), &[])),
+            SourceRepository::from(gist("ccc", Some("backup script"), &[])),
+        ];
+
+        disambiguate_gist_names(&mut repos);
+
+        let names = repos
+            .iter()
+            .map(|repo| repo.name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["Backup-script-aaa", "notes", "backup-script-ccc"]);
+    }
+
+    #[test]
+    fn gist_names_dont_depend_on_the_listing_order() {
+        let mut repos = vec![
+            SourceRepository::from(gist("ccc", Some("backup script"), &[])),
+            SourceRepository::from(gist("aaa", Some("backup script"), &[])),
+        ];
+
+        disambiguate_gist_names(&mut repos);
+
+        assert_eq!(repos[0].name, "backup-script-ccc");
+        assert_eq!(repos[1].name, "backup-script-aaa");
+    }
+}
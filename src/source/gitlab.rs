@@ -0,0 +1,208 @@
+use bytes::Bytes;
+use futures_util::stream::LocalBoxStream;
+use futures_util::{StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::forgejo::models::{ForgejoMigrateRepoService, ForgejoVisibility};
+use crate::gitlab::api::GitlabApi;
+use crate::gitlab::models::GitlabProject;
+use crate::source::models::{
+    SourceOwner, SourceOwnerKind, SourceRepository, SourceRepositoryVisibility,
+};
+use crate::source::SourceProvider;
+
+impl SourceProvider for GitlabApi {
+    fn service(&self) -> ForgejoMigrateRepoService {
+        ForgejoMigrateRepoService::Gitlab
+    }
+
+    fn auth_token(&self) -> String {
+        self.api_key().to_string()
+    }
+
+    async fn get_owner(
+        &mut self,
+        kind: SourceOwnerKind,
+        name: &str,
+    ) -> anyhow::Result<SourceOwner> {
+        match kind {
+            SourceOwnerKind::Organisation => {
+                let group = self.get_group(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: group.full_path,
+                    name: group.path,
+                    display_name: Some(group.full_name),
+                    description: group.description,
+                    website: None,
+                    location: None,
+                    html_url: group.web_url,
+                    visibility: visibility(&group.visibility),
+                })
+            }
+            SourceOwnerKind::User => {
+                let user = self.get_user(name).await?;
+
+                Ok(SourceOwner {
+                    kind,
+                    login: user.username.clone(),
+                    name: user.username,
+                    display_name: Some(user.name),
+                    description: None,
+                    website: user.website_url.filter(|website| !website.is_empty()),
+                    location: user.location.filter(|location| !location.is_empty()),
+                    html_url: user.web_url,
+                    visibility: None,
+                })
+            }
+        }
+    }
+
+    async fn get_owner_avatar(&mut self, owner: &SourceOwner) -> anyhow::Result<Option<Bytes>> {
+        match owner.kind {
+            SourceOwnerKind::Organisation => self.get_group_avatar(&owner.login).await,
+            SourceOwnerKind::User => self.get_user_avatar(&owner.login).await,
+        }
+    }
+
+    fn get_repositories<'a>(
+        &'a mut self,
+        owner: &'a SourceOwner,
+    ) -> LocalBoxStream<'a, anyhow::Result<SourceRepository>> {
+        let projects = match owner.kind {
+            SourceOwnerKind::Organisation => self.get_projects_of_group(&owner.login),
+            SourceOwnerKind::User => self.get_projects_of_user(&owner.login),
+        };
+
+        projects
+            .map_ok(|project| {
+                let name = repository_name(&owner.login, &project);
+
+                SourceRepository {
+                    name,
+                    ..SourceRepository::from(project)
+                }
+            })
+            .boxed_local()
+    }
+
+    async fn get_repository(&mut self, url: &str) -> anyhow::Result<SourceRepository> {
+        lazy_static! {
+            static ref PROJECT_PATH_REGEX: Regex =
+                Regex::new(r"^(?:git@|https?://)[^/:]+[:/](?<projectPath>.+?)(?:\.git)?/?$")
+                    .unwrap();
+        }
+
+        let project_path = match PROJECT_PATH_REGEX.captures(url) {
+            Some(captures) => captures["projectPath"].to_string(),
+            None => url.to_string(),
+        };
+
+        let project = self.get_project(&project_path).await?;
+
+        Ok(SourceRepository::from(project))
+    }
+}
+
+impl From<GitlabProject> for SourceRepository {
+    fn from(project: GitlabProject) -> Self {
+        SourceRepository {
+            id: project.id.to_string(),
+            name: project.path,
+            full_name: project.path_with_namespace,
+            owner: project.namespace.full_path,
+            description: project.description,
+            clone_url: project.http_url_to_repo,
+            html_url: project.web_url,
+            homepage: None,
+            default_branch: project.default_branch,
+            private: project.visibility != "public",
+            fork: project.forked_from_project.is_some(),
+            archived: project.archived,
+            size: 0,
+            empty: Some(project.empty_repo),
+            language: None,
+            topics: project.topics,
+            pushed_at: Some(project.last_activity_at),
+            template: false,
+            disabled: false,
+            visibility: SourceRepositoryVisibility::from(project.visibility.as_str()),
+        }
+    }
+}
+
+/// Projects of subgroups are flattened into the forgejo organisation, so their subgroup path is
+/// prepended to the repository name to avoid collisions, e.g. `subgroup/project` becomes `subgroup-project`
+fn repository_name(namespace: &str, project: &GitlabProject) -> String {
+    let relative_path = project
+        .path_with_namespace
+        .strip_prefix(&format!("{}/", namespace.trim_end_matches('/')))
+        .unwrap_or(&project.path);
+
+    relative_path.replace('/', "-")
+}
+
+fn visibility(visibility: &str) -> Option<ForgejoVisibility> {
+    match visibility {
+        "public" => Some(ForgejoVisibility::Public),
+        "internal" => Some(ForgejoVisibility::Limited),
+        "private" => Some(ForgejoVisibility::Private),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path_with_namespace: &str) -> GitlabProject {
+        GitlabProject {
+            path: path_with_namespace.rsplit('/').next().unwrap().to_string(),
+            path_with_namespace: path_with_namespace.to_string(),
+            ..GitlabProject::default()
+        }
+    }
+
+    #[test]
+    fn repository_name_keeps_projects_of_the_namespace() {
+        assert_eq!(
+            repository_name("group", &project("group/project")),
+            "project"
+        );
+        assert_eq!(repository_name("user", &project("user/project")), "project");
+    }
+
+    #[test]
+    fn repository_name_prepends_the_subgroup_path() {
+        assert_eq!(
+            repository_name("group", &project("group/subgroup/project")),
+            "subgroup-project"
+        );
+        assert_eq!(
+            repository_name("group/subgroup", &project("group/subgroup/nested/project")),
+            "nested-project"
+        );
+    }
+
+    #[test]
+    fn repository_name_only_strips_the_whole_namespace() {
+        assert_eq!(
+            repository_name("group", &project("groupfoo/project")),
+            "project"
+        );
+        assert_eq!(
+            repository_name("group", &project("groupfoo/subgroup/project")),
+            "project"
+        );
+    }
+
+    #[test]
+    fn repository_name_ignores_a_trailing_slash_of_the_namespace() {
+        assert_eq!(
+            repository_name("group/", &project("group/subgroup/project")),
+            "subgroup-project"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+use futures_util::stream::LocalBoxStream;
+
+use crate::forgejo::models::ForgejoMigrateRepoService;
+use crate::source::models::{SourceOwner, SourceOwnerKind, SourceRepository};
+
+mod gitea;
+pub mod github;
+mod gitlab;
+pub mod models;
+
+/// A forge repositories can be mirrored from
+pub trait SourceProvider {
+    /// the service forgejo uses to migrate repositories of this provider
+    fn service(&self) -> ForgejoMigrateRepoService;
+
+    /// the token forgejo uses to access repositories of this provider
+    fn auth_token(&self) -> String;
+
+    async fn get_owner(&mut self, kind: SourceOwnerKind, name: &str)
+        -> anyhow::Result<SourceOwner>;
+
+    async fn get_owner_avatar(&mut self, owner: &SourceOwner) -> anyhow::Result<Option<Bytes>>;
+
+    /// Lists the repositories of the owner page by page, so they can be processed before all pages are fetched
+    fn get_repositories<'a>(
+        &'a mut self,
+        owner: &'a SourceOwner,
+    ) -> LocalBoxStream<'a, anyhow::Result<SourceRepository>>;
+
+    /// Fetches a single repository by its url or its full name
+    async fn get_repository(&mut self, url: &str) -> anyhow::Result<SourceRepository>;
+}
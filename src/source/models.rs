@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+
+use clap::ValueEnum;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::forgejo::models::ForgejoVisibility;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceOwnerKind {
+    Organisation,
+    User,
+}
+
+impl Display for SourceOwnerKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceOwnerKind::Organisation => write!(f, "organisation"),
+            SourceOwnerKind::User => write!(f, "user"),
+        }
+    }
+}
+
+/// An organisation, group or user of a source provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceOwner {
+    pub kind: SourceOwnerKind,
+    /// the identifier of the owner on the source provider, e.g. the full path of a gitlab group
+    pub login: String,
+    /// the name the forgejo organisation gets by default
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub location: Option<String>,
+    pub html_url: String,
+    pub visibility: Option<ForgejoVisibility>,
+}
+
+/// A repository of a source provider
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRepository {
+    /// the stable identifier of the repository on the source provider
+    pub id: String,
+    /// the name the forgejo repository gets by default
+    pub name: String,
+    pub full_name: String,
+    pub owner: String,
+    pub description: Option<String>,
+    pub clone_url: String,
+    pub html_url: String,
+    pub homepage: Option<String>,
+    pub default_branch: Option<String>,
+    pub private: bool,
+    pub fork: bool,
+    pub archived: bool,
+    pub size: i64,
+    /// whether the repository has no commits, None if the source provider doesn't tell
+    #[serde(default)]
+    pub empty: Option<bool>,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
+    pub pushed_at: Option<String>,
+    #[serde(default)]
+    pub template: bool,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub visibility: SourceRepositoryVisibility,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceRepositoryVisibility {
+    #[default]
+    Public,
+    Internal,
+    Private,
+}
+
+impl From<&str> for SourceRepositoryVisibility {
+    fn from(visibility: &str) -> Self {
+        match visibility {
+            "private" => SourceRepositoryVisibility::Private,
+            "internal" => SourceRepositoryVisibility::Internal,
+            _ => SourceRepositoryVisibility::Public,
+        }
+    }
+}
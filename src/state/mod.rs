@@ -0,0 +1,280 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use log::debug;
+use reqwest::Url;
+use tokio::fs;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::cli::RunOptions;
+use crate::source::models::SourceRepository;
+use crate::state::models::{
+    JournalOwner, JournalRepository, JournalRepositoryStatus, StateJournal,
+};
+
+pub mod models;
+
+/// A handle to the state file which records the migration state of every repository,
+/// every change is written to disk immediately so an interrupted run can be resumed
+#[derive(Clone)]
+pub struct Journal {
+    /// the state file changes are written to, None if the state is only kept in memory
+    path: Option<PathBuf>,
+    state: Arc<Mutex<StateJournal>>,
+    /// the revision of the latest change to the state
+    revision: Arc<AtomicU64>,
+    /// the revision of the state which was written last, held while writing
+    written: Arc<Mutex<u64>>,
+}
+
+impl Journal {
+    /// Loads the state file of the run if the run has one, changes are never persisted in dry run mode
+    pub async fn open(run: &RunOptions) -> anyhow::Result<Self> {
+        let path = run.state_file();
+
+        let state = match &path {
+            Some(path) if fs::try_exists(path).await? => {
+                serde_json::from_str::<StateJournal>(&fs::read_to_string(path).await?)?
+            }
+            _ => StateJournal::default(),
+        };
+
+        Ok(Self {
+            path: path.filter(|_| !run.dry_run),
+            state: Arc::new(Mutex::new(state)),
+            revision: Arc::new(AtomicU64::new(0)),
+            written: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    /// Returns the repositories recorded for the owner by a previous run, if that run listed all of them
+    pub async fn owner_repositories(
+        &self,
+        source: &str,
+        target: &str,
+    ) -> Option<Vec<SourceRepository>> {
+        let state = self.state.lock().await;
+
+        let owner = state
+            .owners
+            .get(&journal_key(source, target))
+            .filter(|owner| owner.complete)?;
+
+        Some(
+            owner
+                .repositories
+                .iter()
+                .filter_map(|key| state.repositories.get(key))
+                .map(|entry| match &entry.repository {
+                    Some(repository) => repository.clone(),
+                    // done repositories are skipped, so only what identifies them is kept
+                    None => SourceRepository {
+                        id: entry.id.clone(),
+                        name: entry.name.clone(),
+                        clone_url: entry.source.clone(),
+                        ..SourceRepository::default()
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    /// Starts a new listing of the repositories of the owner, replacing the previous listing
+    pub async fn start_owner(&self, source: &str, target: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        state.owners.insert(
+            journal_key(source, target),
+            JournalOwner {
+                source: source.to_string(),
+                target: target.to_string(),
+                repositories: Vec::new(),
+                complete: false,
+            },
+        );
+
+        self.save(state).await
+    }
+
+    /// Adds a listed repository to the owner, repositories without a recorded state are pending
+    pub async fn record_owner_repository(
+        &self,
+        source: &str,
+        target: &str,
+        repository: &SourceRepository,
+    ) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        let repository_target = format!("{}/{}", target, repository.name);
+        let key = journal_key(&repository.clone_url, &repository_target);
+
+        let entry = state
+            .repositories
+            .entry(key.clone())
+            .or_insert_with(|| JournalRepository {
+                source: repository.clone_url.clone(),
+                target: repository_target,
+                status: JournalRepositoryStatus::Pending,
+                reason: None,
+                id: repository.id.clone(),
+                name: repository.name.clone(),
+                repository: None,
+            });
+
+        entry.id = repository.id.clone();
+        entry.name = repository.name.clone();
+        entry.repository =
+            (entry.status != JournalRepositoryStatus::Done).then(|| repository.clone());
+
+        if let Some(owner) = state.owners.get_mut(&journal_key(source, target)) {
+            owner.repositories.push(key);
+        }
+
+        self.save(state).await
+    }
+
+    /// Marks the listing of the owner as complete
+    pub async fn complete_owner(&self, source: &str, target: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        if let Some(owner) = state.owners.get_mut(&journal_key(source, target)) {
+            owner.complete = true;
+        }
+
+        self.save(state).await
+    }
+
+    pub async fn is_done(&self, repository: &SourceRepository, target: &str) -> bool {
+        let state = self.state.lock().await;
+
+        state
+            .repositories
+            .get(&journal_key(&repository.clone_url, target))
+            .is_some_and(|entry| entry.status == JournalRepositoryStatus::Done)
+    }
+
+    /// Returns the state of the repository recorded by a previous run
+    pub async fn get(
+        &self,
+        repository: &SourceRepository,
+        target: &str,
+    ) -> Option<JournalRepository> {
+        let state = self.state.lock().await;
+
+        state
+            .repositories
+            .get(&journal_key(&repository.clone_url, target))
+            .cloned()
+    }
+
+    pub async fn set_status(
+        &self,
+        repository: &SourceRepository,
+        target: &str,
+        status: JournalRepositoryStatus,
+        reason: Option<String>,
+    ) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        state.repositories.insert(
+            journal_key(&repository.clone_url, target),
+            JournalRepository {
+                source: repository.clone_url.clone(),
+                target: target.to_string(),
+                status,
+                reason,
+                id: repository.id.clone(),
+                name: repository.name.clone(),
+                repository: (status != JournalRepositoryStatus::Done).then(|| repository.clone()),
+            },
+        );
+
+        self.save(state).await
+    }
+
+    /// Finds the mirror of the repository made by a previous run under another source url and target,
+    /// i.e. the repository was renamed or transferred on the source since then
+    pub async fn find_moved(
+        &self,
+        repository: &SourceRepository,
+        target: &str,
+    ) -> Option<JournalRepository> {
+        if repository.id.is_empty() {
+            return None;
+        }
+
+        let state = self.state.lock().await;
+
+        // a repository mirrored to several targets under the same source url is mirrored on purpose
+        let mut moved = state
+            .repositories
+            .values()
+            .filter(|entry| {
+                entry.status == JournalRepositoryStatus::Done
+                    && entry.id == repository.id
+                    && entry.source != repository.clone_url
+                    && entry.target != target
+                    && host(&entry.source) == host(&repository.clone_url)
+            })
+            .collect::<Vec<_>>();
+
+        // a renamed repository stays with its owner, so a previous mirror in the same owner is preferred
+        let owner = target.split_once('/').map(|(owner, _)| owner);
+        moved.sort_by_key(|entry| entry.target.split_once('/').map(|(owner, _)| owner) != owner);
+
+        moved.first().map(|entry| (*entry).clone())
+    }
+
+    /// Removes the state of a repository, e.g. after its mirror was moved to another target
+    pub async fn forget(&self, source: &str, target: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+
+        state.repositories.remove(&journal_key(source, target));
+
+        self.save(state).await
+    }
+
+    /// Writes the state to the state file, the state is serialized while it's locked but written
+    /// after releasing the lock, so other migrations don't wait for the disk
+    async fn save(&self, state: MutexGuard<'_, StateJournal>) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let content = serde_json::to_string(&*state)?;
+        let revision = self.revision.fetch_add(1, Ordering::SeqCst) + 1;
+
+        drop(state);
+
+        let mut written = self.written.lock().await;
+
+        // a later change was written while waiting, writing this one would revert it
+        if *written > revision {
+            return Ok(());
+        }
+
+        debug!("Writing state file: {}", path.display());
+
+        // write to a temporary file first so an interrupted write never corrupts the state file
+        let temporary_path = path.with_extension("json.tmp");
+
+        fs::write(&temporary_path, content).await?;
+        fs::rename(&temporary_path, path).await?;
+
+        *written = revision;
+
+        Ok(())
+    }
+}
+
+fn host(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+}
+
+/// Owners and repositories are both keyed by their source url and their target on forgejo
+fn journal_key(source: &str, target: &str) -> String {
+    format!("{} -> {}", source, target)
+}
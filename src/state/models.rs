@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::source::models::SourceRepository;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateJournal {
+    /// the repositories of every mirrored owner, keyed by source and target owner
+    #[serde(default)]
+    pub owners: BTreeMap<String, JournalOwner>,
+    /// the state of every repository, keyed by source and target repository
+    #[serde(default)]
+    pub repositories: BTreeMap<String, JournalRepository>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalOwner {
+    pub source: String,
+    pub target: String,
+    /// the keys of all repositories of this owner in `StateJournal::repositories`
+    pub repositories: Vec<String>,
+    /// whether all repositories of the owner were listed, only complete listings are reused when resuming
+    #[serde(default)]
+    pub complete: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalRepository {
+    pub source: String,
+    pub target: String,
+    pub status: JournalRepositoryStatus,
+    /// the error of the last attempt if the migration failed
+    pub reason: Option<String>,
+    /// the stable identifier of the source repository, used to find the mirror after the repository moved
+    pub id: String,
+    pub name: String,
+    /// the listed source repository, only kept until it's migrated since a resumed run skips done repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<SourceRepository>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalRepositoryStatus {
+    #[default]
+    Pending,
+    Migrating,
+    Done,
+    Failed,
+}
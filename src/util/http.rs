@@ -1,6 +1,12 @@
 use const_format::formatcp;
+use futures_util::future;
+use futures_util::stream::{self, LocalBoxStream};
+use futures_util::{StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
-use reqwest::Client;
+use log::debug;
+use reqwest::header::{HeaderMap, LINK};
+use reqwest::{Client, Response, Url};
+use serde::de::DeserializeOwned;
 
 use crate::built_info;
 
@@ -8,9 +14,308 @@ lazy_static! {
     pub static ref CLIENT: Client = Client::new();
 }
 
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
 pub const USER_AGENT: &str = formatcp!(
     "{}/{} ({})",
     built_info::PKG_NAME,
     built_info::PKG_VERSION,
     built_info::PKG_HOMEPAGE
 );
+
+/// A client which can request the pages of a paginated list endpoint
+pub trait PageClient {
+    async fn get_page(&mut self, url: Url) -> anyhow::Result<Response>;
+}
+
+/// Streams all items of a paginated list endpoint, the url has to contain the page size already.
+///
+/// The next page is taken from the rfc 5988 `Link` header if present (github, gitlab), otherwise
+/// from the `X-Total-Count` header (forgejo), otherwise pages are requested until one is empty.
+/// A page is only requested once all items of the previous page were consumed.
+pub fn paginate<'a, C, T>(client: &'a mut C, url: &str) -> LocalBoxStream<'a, anyhow::Result<T>>
+where
+    C: PageClient,
+    T: DeserializeOwned + 'a,
+{
+    let first_page = match Url::parse(url) {
+        Ok(url) => with_page(&url, 1),
+        Err(err) => return stream::once(future::ready(Err(err.into()))).boxed_local(),
+    };
+
+    stream::try_unfold(
+        (client, Some(first_page), 1, 0),
+        |(client, next_page, page, fetched)| async move {
+            let url = match next_page {
+                Some(url) => url,
+                None => return anyhow::Ok(None),
+            };
+
+            let (items, next_page) = fetch_page::<C, T>(client, url, page, fetched).await?;
+            let fetched = fetched + items.len();
+
+            Ok(Some((items, (client, next_page, page + 1, fetched))))
+        },
+    )
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed_local()
+}
+
+/// Fetches a single page and determines the url of the next page, if there is one
+async fn fetch_page<C: PageClient, T: DeserializeOwned>(
+    client: &mut C,
+    url: Url,
+    page: usize,
+    fetched: usize,
+) -> anyhow::Result<(Vec<T>, Option<Url>)> {
+    let res = client.get_page(url.clone()).await?;
+
+    let has_links = res.headers().contains_key(LINK);
+    let next_link = next_link(res.headers());
+    let total_count = res
+        .headers()
+        .get(TOTAL_COUNT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+
+    let items = res.json::<Vec<T>>().await?;
+    let fetched = fetched + items.len();
+
+    debug!(
+        "Fetched page {} with {} items from {}",
+        page,
+        items.len(),
+        url.path()
+    );
+
+    let next_page = match (next_link, total_count) {
+        (Some(next_link), _) => Some(Url::parse(&next_link)?),
+        // the last page only links back, guessing a further page would break cursor based pagination
+        (None, _) if has_links => None,
+        (None, Some(total_count)) if fetched < total_count && !items.is_empty() => {
+            Some(with_page(&url, page + 1))
+        }
+        (None, Some(_)) => None,
+        (None, None) if !items.is_empty() => Some(with_page(&url, page + 1)),
+        (None, None) => None,
+    };
+
+    Ok((items, next_page))
+}
+
+fn with_page(url: &Url, page: usize) -> Url {
+    let mut url = url.clone();
+
+    let query = url
+        .query_pairs()
+        .filter(|(key, _)| key != "page")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<(String, String)>>();
+
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(query)
+        .append_pair("page", &page.to_string());
+
+    url
+}
+
+/// Parses the url of the next page from a `Link` header like `<https://api.github.com/...&page=2>; rel="next"`
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let (url, params) = link.split_once(';')?;
+
+            let is_next = params.split(';').any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("rel=")
+                    .is_some_and(|rel| rel.trim_matches('"').split(' ').any(|rel| rel == "next"))
+            });
+
+            is_next.then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    /// Answers the page requests with canned responses and records the requested urls
+    #[derive(Default)]
+    struct FakePageClient {
+        responses: VecDeque<(Vec<(&'static str, String)>, String)>,
+        requested: Vec<String>,
+    }
+
+    impl FakePageClient {
+        fn respond(mut self, headers: &[(&'static str, &str)], items: &[u32]) -> Self {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| (*name, value.to_string()))
+                .collect();
+
+            self.responses
+                .push_back((headers, serde_json::to_string(items).unwrap()));
+            self
+        }
+    }
+
+    impl PageClient for FakePageClient {
+        async fn get_page(&mut self, url: Url) -> anyhow::Result<Response> {
+            self.requested.push(url.to_string());
+
+            let (headers, body) = self
+                .responses
+                .pop_front()
+                .ok_or(anyhow::anyhow!("unexpected request of {}", url))?;
+
+            let mut response = http::Response::builder();
+
+            for (name, value) in headers {
+                response = response.header(name, value);
+            }
+
+            Ok(Response::from(response.body(body)?))
+        }
+    }
+
+    async fn collect(client: &mut FakePageClient, url: &str) -> anyhow::Result<Vec<u32>> {
+        paginate::<_, u32>(client, url).try_collect().await
+    }
+
+    fn link_headers(values: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        for value in values {
+            headers.append(LINK, HeaderValue::from_static(value));
+        }
+
+        headers
+    }
+
+    #[test]
+    fn next_link_finds_the_next_relation() {
+        let headers = link_headers(&[
+            r#"<https://api.github.com/orgs/o/repos?page=1>; rel="prev", <https://api.github.com/orgs/o/repos?page=3>; rel="next", <https://api.github.com/orgs/o/repos?page=9>; rel="last""#,
+        ]);
+
+        assert_eq!(
+            next_link(&headers),
+            Some("https://api.github.com/orgs/o/repos?page=3".to_string())
+        );
+    }
+
+    #[test]
+    fn next_link_supports_multiple_headers_and_relations() {
+        let headers = link_headers(&[
+            r#"<https://gitlab.com/api/v4/groups?page=1>; rel="first""#,
+            r#"<https://gitlab.com/api/v4/groups?page=2>; title="more"; rel="next last""#,
+        ]);
+
+        assert_eq!(
+            next_link(&headers),
+            Some("https://gitlab.com/api/v4/groups?page=2".to_string())
+        );
+    }
+
+    #[test]
+    fn next_link_is_missing_on_the_last_page() {
+        let headers = link_headers(&[r#"<https://api.github.com/repos?page=1>; rel="first""#]);
+
+        assert_eq!(next_link(&headers), None);
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn with_page_replaces_the_page_parameter() {
+        let url = Url::parse("https://example.com/api?limit=50&page=3").unwrap();
+
+        assert_eq!(
+            with_page(&url, 4).as_str(),
+            "https://example.com/api?limit=50&page=4"
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_link_headers() {
+        let mut client = FakePageClient::default()
+            .respond(
+                &[("link", r#"<https://example.com/api?cursor=b>; rel="next""#)],
+                &[1, 2],
+            )
+            .respond(
+                &[("link", r#"<https://example.com/api?cursor=a>; rel="prev""#)],
+                &[3],
+            );
+
+        let items = collect(&mut client, "https://example.com/api?per_page=2")
+            .await
+            .unwrap();
+
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(
+            client.requested,
+            [
+                "https://example.com/api?per_page=2&page=1",
+                "https://example.com/api?cursor=b"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_total_count() {
+        let mut client = FakePageClient::default()
+            .respond(&[("x-total-count", "3")], &[1, 2])
+            .respond(&[("x-total-count", "3")], &[3]);
+
+        let items = collect(&mut client, "https://example.com/api?limit=2")
+            .await
+            .unwrap();
+
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(client.requested.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stops_at_an_empty_page_without_pagination_headers() {
+        let mut client = FakePageClient::default()
+            .respond(&[], &[1, 2])
+            .respond(&[], &[]);
+
+        let items = collect(&mut client, "https://example.com/api?limit=2")
+            .await
+            .unwrap();
+
+        assert_eq!(items, [1, 2]);
+        assert_eq!(
+            client.requested,
+            [
+                "https://example.com/api?limit=2&page=1",
+                "https://example.com/api?limit=2&page=2"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_on_invalid_urls() {
+        let mut client = FakePageClient::default();
+
+        assert!(collect(&mut client, "not a url").await.is_err());
+        assert!(client.requested.is_empty());
+    }
+}
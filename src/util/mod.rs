@@ -1 +1,3 @@
 pub mod http;
+pub mod retry;
+pub mod time;
@@ -0,0 +1,302 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use log::warn;
+use reqwest::{Method, Request, Response, StatusCode};
+use tokio::time::sleep;
+
+/// Decides which failed requests are retried and how long to wait before each retry
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// how often a request is attempted in total, 1 disables retries
+    pub max_attempts: u32,
+    /// the delay before the first retry, it is doubled for every further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// if set then a random delay between zero and the backoff is used, so concurrent requests don't retry in lockstep
+    pub jitter: bool,
+    pub retryable_status_codes: Vec<StatusCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retryable_status_codes: vec![
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether another attempt is allowed after the given attempt, starting at 1
+    pub fn can_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Whether the request can be sent again without side effects, other requests like migrations
+    /// have to check whether the first attempt succeeded before retrying
+    pub fn is_idempotent(method: &Method) -> bool {
+        matches!(
+            *method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        )
+    }
+
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_status_codes.contains(&status)
+    }
+
+    /// Whether the error is a transient failure to connect or a timeout
+    pub fn is_retryable_error(&self, err: &anyhow::Error) -> bool {
+        err.chain().any(|cause| {
+            if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+                return err.is_timeout() || err.is_connect();
+            }
+
+            if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+                return matches!(
+                    err.kind(),
+                    ErrorKind::ConnectionRefused | ErrorKind::TimedOut
+                );
+            }
+
+            cause.is::<tower::timeout::error::Elapsed>()
+        })
+    }
+
+    /// Sends the request through the service with `send` until it succeeds, fails permanently or
+    /// all attempts are used up. Requests which aren't idempotent or whose body can't be cloned are
+    /// only sent once, the name of the service is used in the logged retries
+    pub async fn retry<S>(
+        &self,
+        name: &str,
+        service: &mut S,
+        req: Request,
+        mut send: impl for<'a> FnMut(&'a mut S, Request) -> BoxFuture<'a, anyhow::Result<Response>>,
+    ) -> anyhow::Result<Response> {
+        let mut req = req;
+        let mut attempt = 1;
+
+        loop {
+            let next_req = if Self::is_idempotent(req.method()) && self.can_retry(attempt) {
+                req.try_clone()
+            } else {
+                None
+            };
+
+            let url = req.url().clone();
+            let result = send(service, req).await;
+
+            let failure = match &result {
+                Ok(res) if self.is_retryable_status(res.status()) => res.status().to_string(),
+                Err(err) if self.is_retryable_error(err) => err.to_string(),
+                _ => return result,
+            };
+
+            let Some(next_req) = next_req else {
+                return result;
+            };
+
+            let backoff = self.backoff(attempt);
+
+            warn!(
+                "{} request to {} failed ({}), retrying in {}ms (attempt {}/{})",
+                name,
+                url,
+                failure,
+                backoff.as_millis(),
+                attempt + 1,
+                self.max_attempts
+            );
+
+            sleep(backoff).await;
+
+            req = next_req;
+            attempt += 1;
+        }
+    }
+
+    /// The delay before the next attempt after the given attempt failed, starting at 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return backoff;
+        }
+
+        // every RandomState is seeded differently, which is random enough for spreading out retries
+        let random = RandomState::new().build_hasher().finish();
+
+        backoff.mul_f64((random % 1000) as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    fn policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            jitter,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_with_every_attempt() {
+        let policy = policy(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = policy(false);
+
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn backoff_of_the_first_attempt_is_the_initial_backoff() {
+        assert_eq!(policy(false).backoff(0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_stays_below_the_backoff() {
+        let jittered = policy(true);
+        let fixed = policy(false);
+
+        for attempt in 1..10 {
+            assert!(jittered.backoff(attempt) <= fixed.backoff(attempt));
+        }
+    }
+
+    #[test]
+    fn retries_until_the_maximum_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            ..RetryPolicy::default()
+        };
+
+        assert!(policy.can_retry(1));
+        assert!(policy.can_retry(2));
+        assert!(!policy.can_retry(3));
+    }
+
+    #[test]
+    fn only_idempotent_methods_are_retried_blindly() {
+        assert!(RetryPolicy::is_idempotent(&Method::GET));
+        assert!(RetryPolicy::is_idempotent(&Method::DELETE));
+        assert!(!RetryPolicy::is_idempotent(&Method::POST));
+        assert!(!RetryPolicy::is_idempotent(&Method::PATCH));
+    }
+
+    #[test]
+    fn retries_the_configured_status_codes() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!policy.is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!policy.is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[test]
+    fn retries_connect_errors_and_timeouts() {
+        let policy = RetryPolicy::default();
+
+        let refused = anyhow::Error::new(std::io::Error::from(ErrorKind::ConnectionRefused));
+        let timed_out = anyhow::Error::new(std::io::Error::from(ErrorKind::TimedOut));
+        let reset = anyhow::Error::new(std::io::Error::from(ErrorKind::ConnectionReset));
+        let denied = anyhow::Error::new(std::io::Error::from(ErrorKind::PermissionDenied));
+
+        assert!(policy.is_retryable_error(&refused.context("request failed")));
+        assert!(policy.is_retryable_error(&timed_out));
+        assert!(!policy.is_retryable_error(&reset));
+        assert!(!policy.is_retryable_error(&denied));
+        assert!(!policy.is_retryable_error(&anyhow::anyhow!("invalid response")));
+    }
+
+    /// Answers the sent requests with the given status codes and counts the requests
+    async fn send_with_statuses(
+        policy: &RetryPolicy,
+        method: Method,
+        statuses: &[u16],
+    ) -> (StatusCode, usize) {
+        let mut statuses = statuses.iter().copied().collect::<VecDeque<_>>();
+        let mut sent = 0;
+
+        let req = Request::new(method, "http://localhost/repos".parse().unwrap());
+
+        let res = policy
+            .retry("Test", &mut statuses, req, |statuses, _| {
+                sent += 1;
+
+                Box::pin(async move {
+                    let status = statuses.pop_front().expect("unexpected request");
+
+                    Ok(Response::from(
+                        http::Response::builder().status(status).body("")?,
+                    ))
+                })
+            })
+            .await
+            .unwrap();
+
+        (res.status(), sent)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            jitter: false,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_until_a_response_is_not_retryable() {
+        let (status, sent) =
+            send_with_statuses(&fast_policy(), Method::GET, &[503, 502, 200]).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(sent, 3);
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_response_once_the_attempts_are_used_up() {
+        let (status, sent) =
+            send_with_statuses(&fast_policy(), Method::GET, &[503, 503, 503]).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(sent, 3);
+    }
+
+    #[tokio::test]
+    async fn sends_requests_which_are_not_idempotent_once() {
+        let (status, sent) = send_with_statuses(&fast_policy(), Method::POST, &[503]).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(sent, 1);
+    }
+}
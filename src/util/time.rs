@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime};
+
+/// Parses the timestamps returned by the apis of the source providers, e.g. `2024-01-31T12:00:00Z`,
+/// `2024-01-31T12:00:00.000Z` or `2024-01-31T12:00:00+01:00`
+pub fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let bytes = timestamp.as_bytes();
+
+    // humantime doesn't support utc offsets like `+01:00`, so they are applied manually
+    let has_offset = bytes.len() >= "2024-01-31T12:00:00+01:00".len()
+        && matches!(bytes[bytes.len() - 6], b'+' | b'-')
+        && bytes[bytes.len() - 3] == b':';
+
+    if !has_offset {
+        return humantime::parse_rfc3339_weak(timestamp).ok();
+    }
+
+    let (date_time, offset) = timestamp.split_at(timestamp.len() - 6);
+
+    let time = humantime::parse_rfc3339_weak(date_time).ok()?;
+
+    let hours = offset[1..3].parse::<u64>().ok()?;
+    let minutes = offset[4..6].parse::<u64>().ok()?;
+    let offset_duration = Duration::from_secs(hours * 3600 + minutes * 60);
+
+    // the local time is ahead of utc for positive offsets
+    if offset.starts_with('+') {
+        time.checked_sub(offset_duration)
+    } else {
+        time.checked_add(offset_duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    /// 2024-01-31T12:00:00Z
+    fn noon() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_706_702_400)
+    }
+
+    #[test]
+    fn parses_utc_timestamps() {
+        assert_eq!(parse_timestamp("2024-01-31T12:00:00Z"), Some(noon()));
+    }
+
+    #[test]
+    fn parses_fractional_seconds() {
+        assert_eq!(
+            parse_timestamp("2024-01-31T12:00:00.250Z"),
+            Some(noon() + Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn applies_utc_offsets() {
+        assert_eq!(parse_timestamp("2024-01-31T13:00:00+01:00"), Some(noon()));
+        assert_eq!(parse_timestamp("2024-01-31T06:30:00-05:30"), Some(noon()));
+        assert_eq!(
+            parse_timestamp("2024-01-31T13:00:00.500+01:00"),
+            Some(noon() + Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_timestamps() {
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2024-13-31T12:00:00Z"), None);
+        assert_eq!(parse_timestamp("2024-01-31T12:00:00+xx:00"), None);
+    }
+}